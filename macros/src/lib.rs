@@ -0,0 +1,218 @@
+//! `openapi!` macro generating the api from the spec at compile time
+
+use std::{env, path::PathBuf};
+
+use cargo_actix_openapi::{ApiOptions, Extractor, Metrics, OpenapiWithMeta};
+use clap::ValueEnum;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, LitBool, LitStr, Token,
+};
+
+/// Argument of the macro, either path of the spec or `name = value` option
+enum Arg {
+    Spec(LitStr),
+    Flag(Ident, LitBool),
+    Value(Ident, LitStr),
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            return Ok(Arg::Spec(input.parse()?));
+        }
+
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        match input.peek(LitBool) {
+            true => Ok(Arg::Flag(name, input.parse()?)),
+            false => Ok(Arg::Value(name, input.parse()?)),
+        }
+    }
+}
+
+/// Absolute path of the file given relative to the crate being compiled
+fn manifest_path(path: &LitStr) -> syn::Result<String> {
+    let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") else {
+        return Err(syn::Error::new(
+            path.span(),
+            "CARGO_MANIFEST_DIR is not set",
+        ));
+    };
+
+    let absolute = PathBuf::from(manifest_dir).join(path.value());
+
+    match absolute.to_str() {
+        Some(value) => Ok(value.to_string()),
+        None => Err(syn::Error::new(
+            path.span(),
+            format!(
+                "Could not represent path {} as string",
+                absolute.to_string_lossy()
+            ),
+        )),
+    }
+}
+
+fn expand(args: Punctuated<Arg, Token![,]>) -> syn::Result<proc_macro2::TokenStream> {
+    let mut spec_paths = Vec::new();
+    let mut title = None;
+    let mut templates = None;
+    let mut options = ApiOptions {
+        included: true,
+        ..Default::default()
+    };
+
+    for arg in args {
+        match arg {
+            Arg::Spec(path) => spec_paths.push(path),
+            Arg::Flag(name, value) => match name.to_string().as_str() {
+                "mock" => options.mock = value.value,
+                "document_errors" => options.document_errors = value.value,
+                "tracing" => options.tracing = value.value,
+                "split_by_tag" => options.split_by_tag = value.value,
+                _ => return Err(syn::Error::new(name.span(), "Unknown flag")),
+            },
+            Arg::Value(name, value) => {
+                let error =
+                    |error: anyhow::Error| syn::Error::new(value.span(), format!("{error:#}"));
+
+                match name.to_string().as_str() {
+                    "metrics" => {
+                        options.metrics = Metrics::from_str(&value.value(), false)
+                            .map_err(|error| syn::Error::new(value.span(), error))?
+                    }
+                    "extractor" => options
+                        .extractors
+                        .push(value.value().parse::<Extractor>().map_err(error)?),
+                    "derive" => options.derives.push(value.value()),
+                    "type_map" => {
+                        let (format, type_) =
+                            cargo_actix_openapi::parse_type_mapping(&value.value())
+                                .map_err(error)?;
+                        options.type_map.insert(format, type_);
+                    }
+                    "module_path" => {
+                        let path = cargo_actix_openapi::parse_module_path(&value.value());
+                        options.module_path = Some(path.map_err(error)?);
+                    }
+                    "title" => title = Some(value.value()),
+                    "templates" => templates = Some(value),
+                    _ => return Err(syn::Error::new(name.span(), "Unknown option")),
+                }
+            }
+        }
+    }
+
+    let Some(first_spec) = spec_paths.first() else {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Expected path of the openapi spec relative to the crate",
+        ));
+    };
+
+    // Files included by the generated code, so that it is recompiled when they change
+    let mut tracked = Vec::new();
+    let mut specs = Vec::new();
+
+    for path in &spec_paths {
+        let absolute = manifest_path(path)?;
+
+        let content = std::fs::read_to_string(&absolute).map_err(|error| {
+            syn::Error::new(path.span(), format!("Could not read {absolute}: {error}"))
+        })?;
+
+        specs.push(OpenapiWithMeta {
+            content,
+            path: absolute.clone(),
+        });
+        tracked.push(absolute);
+    }
+
+    if let Some(literal) = &templates {
+        let dir = PathBuf::from(manifest_path(literal)?);
+
+        let paths = cargo_actix_openapi::custom_templates(&dir)
+            .map_err(|error| syn::Error::new(literal.span(), format!("{error:#}")))?;
+
+        for path in paths {
+            tracked.push(path.to_string_lossy().to_string());
+        }
+
+        options.templates = Some(dir);
+    }
+
+    let docs_path = PathBuf::from(manifest_path(first_spec)?).with_file_name("docs.html");
+
+    let docs_path = match docs_path.to_str() {
+        Some(path) if docs_path.is_file() => {
+            tracked.push(path.to_string());
+            path.to_string()
+        }
+        _ => {
+            let title = title.as_deref().unwrap_or("App");
+            let docs = cargo_actix_openapi::generate_docs(title)
+                .map_err(|error| syn::Error::new(first_spec.span(), format!("{error:#}")))?;
+
+            options.docs_html = Some(docs);
+            "docs.html".to_string()
+        }
+    };
+
+    let (_, generated) =
+        cargo_actix_openapi::generate_api(&docs_path, &specs, &options).map_err(|error| {
+            syn::Error::new(
+                first_spec.span(),
+                format!("Could not generate api: {error:#}"),
+            )
+        })?;
+
+    let generated: proc_macro2::TokenStream = generated.parse().map_err(|error| {
+        syn::Error::new(
+            first_spec.span(),
+            format!("Generated api is not valid rust: {error}"),
+        )
+    })?;
+
+    Ok(quote! {
+        #(const _: &str = include_str!(#tracked);)*
+
+        #generated
+    })
+}
+
+/// Generates the api from openapi specs, given by paths relative to the crate root.
+/// The spec is followed by options of the command line with `_` instead of `-`:
+///
+/// ```ignore
+/// #[macro_use]
+/// pub mod api {
+///     cargo_actix_openapi_macros::openapi!(
+///         "src/server/static/openapi.yaml",
+///         mock = true,
+///         metrics = "none",
+///         extractor = "HttpRequest",
+///         derive = "Hash",
+///         type_map = "uuid=uuid::Uuid",
+///         module_path = "server::api",
+///         templates = "templates",
+///     );
+/// }
+/// ```
+///
+/// `docs.html` next to the first spec is served as the docs page, otherwise the default page
+/// titled by `title` is embedded. Errors of the generation are reported as compile errors.
+#[proc_macro]
+pub fn openapi(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Arg, Token![,]>::parse_terminated);
+
+    match expand(args) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::{http::StatusCode, test, web, App};
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/error.yaml",
+        mock = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{mock::MockApiService, GreetUserError, ProblemDetails};
+
+#[actix_web::test]
+async fn test_problem_details() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.greet_user.push_response(Err(
+        detailed!(GreetUserError::NotFound, "No user bob").instance("/hello/bob")
+    ));
+    mock.greet_user.push_response(Err(detailed!(
+        GreetUserError::NameContainsSpace,
+        "Name is \"b b\""
+    )));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .service(api::make_scope::<MockApiService, MockApiService>()),
+    )
+    .await;
+
+    let request = test::TestRequest::get().uri("/hello/bob").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/problem+json"
+    );
+
+    let problem: ProblemDetails = test::read_body_json(response).await;
+    assert_eq!(problem.type_, "urn:problem:greet-user-error:not-found");
+    assert_eq!(problem.title, "Not found");
+    assert_eq!(problem.status, 404);
+    assert_eq!(problem.detail, "No user bob");
+    assert_eq!(problem.instance.as_deref(), Some("/hello/bob"));
+
+    let request = test::TestRequest::get().uri("/hello/b%20b").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let problem: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(
+        problem,
+        serde_json::json!({
+            "type": "urn:problem:greet-user-error:name-contains-space",
+            "title": "Name contains space",
+            "status": 400,
+            "detail": "Name is \"b b\"",
+        })
+    );
+
+    // Served spec advertises problem details for the error responses
+    let request = test::TestRequest::get().uri("/openapi.yaml").to_request();
+    let body = test::call_and_read_body(&app, request).await;
+    let spec = String::from_utf8(body.to_vec()).unwrap();
+    assert!(spec.contains("application/problem+json"), "{spec}");
+    assert!(spec.contains("ProblemDetails"), "{spec}");
+}
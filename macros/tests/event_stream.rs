@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use actix_web::{http::StatusCode, test, web, App};
+use async_trait::async_trait;
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/event_stream.yaml",
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{
+    ApiService, Detailed, EventStream, JobProgressError, JobProgressEvent, JobProgressPath,
+    SseEvent,
+};
+
+/// Job sending half of the progress at once and the rest after a pause
+struct Jobs;
+
+#[async_trait(?Send)]
+impl ApiService<()> for Jobs {
+    async fn job_progress(
+        _data: web::Data<()>,
+        path: web::Path<JobProgressPath>,
+    ) -> Result<EventStream<JobProgressEvent>, Detailed<JobProgressError>> {
+        if path.job_id != "build" {
+            apibail!(JobProgressError::JobNotFound, "No job of the id");
+        }
+
+        let events = futures::stream::unfold(0, |percent| async move {
+            match percent {
+                0 => {}
+                50 => actix_web::rt::time::sleep(Duration::from_millis(250)).await,
+                _ => return None,
+            }
+
+            let event = SseEvent::new(JobProgressEvent {
+                done: percent == 50,
+                percent: percent + 50,
+            });
+
+            Some((event.id(percent.to_string()), percent + 50))
+        });
+
+        Ok(EventStream::new(events).keep_alive(Duration::from_millis(100)))
+    }
+}
+
+#[actix_web::test]
+async fn test_event_stream() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(()))
+            .service(api::make_scope::<Jobs, ()>()),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/jobs/build/progress")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(response.headers().get("Cache-Control").unwrap(), "no-cache");
+
+    let body = test::read_body(response).await;
+    let body = std::str::from_utf8(&body).unwrap();
+
+    // Keep-alive comments are sent only while waiting for the next event
+    let first = "id: 0\ndata: {\"done\":false,\"percent\":50}\n\n";
+    let last = "id: 50\ndata: {\"done\":true,\"percent\":100}\n\n";
+    let keep_alives = body
+        .strip_prefix(first)
+        .and_then(|rest| rest.strip_suffix(last))
+        .unwrap_or_else(|| panic!("Unexpected events {body:?}"));
+
+    assert!(!keep_alives.is_empty());
+    assert_eq!(
+        keep_alives.replace(": keep-alive\n\n", ""),
+        "",
+        "{keep_alives:?}"
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/jobs/deploy/progress")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
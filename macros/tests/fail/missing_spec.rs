@@ -0,0 +1,5 @@
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(metrics = "none");
+}
+
+fn main() {}
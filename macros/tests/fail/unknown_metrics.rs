@@ -0,0 +1,5 @@
+pub mod api {
+    cargo_actix_openapi_macros::openapi!("../tests/openapi/helloworld.yaml", metrics = "statsd");
+}
+
+fn main() {}
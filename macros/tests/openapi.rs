@@ -0,0 +1,19 @@
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/helloworld.yaml",
+        mock = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+#[test]
+fn test_openapi() {
+    let _ = api::make_scope::<api::mock::MockApiService, api::mock::MockApiService>;
+}
+
+#[test]
+fn test_openapi_fail() {
+    trybuild::TestCases::new().compile_fail("tests/fail/*.rs");
+}
@@ -0,0 +1,82 @@
+use actix_web::{http::StatusCode, test, web, App};
+use serde_json::json;
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/request_body_media.yaml",
+        mock = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{mock::MockApiService, CreateUserBody};
+
+#[actix_web::test]
+async fn test_negotiated_body() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.create_user
+        .respond_with(|call| web::Json(call.body.clone()));
+    mock.create_users
+        .respond_with(|call| web::Json(call.body.len() as i64));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .service(api::make_scope::<MockApiService, MockApiService>()),
+    )
+    .await;
+
+    let user = json!({"name": "rex"});
+
+    // Declared media types are decoded by their format
+    let request = test::TestRequest::post()
+        .uri("/users")
+        .insert_header(("Content-Type", "application/msgpack"))
+        .set_payload(rmp_serde::to_vec_named(&user).unwrap())
+        .to_request();
+    let response: CreateUserBody = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response.name, "rex");
+
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&json!([user, user]), &mut cbor).unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/users/batch")
+        .insert_header(("Content-Type", "application/cbor; charset=utf-8"))
+        .set_payload(cbor)
+        .to_request();
+    let response: i64 = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response, 2);
+
+    // Media types the operation does not declare are rejected before the handler
+    for (uri, content_type) in [
+        ("/users", Some("text/plain")),
+        ("/users", None),
+        ("/users/batch", Some("application/msgpack")),
+    ] {
+        let mut request = test::TestRequest::post()
+            .uri(uri)
+            .set_payload(rmp_serde::to_vec_named(&user).unwrap());
+        if let Some(content_type) = content_type {
+            request = request.insert_header(("Content-Type", content_type));
+        }
+
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "{uri} {content_type:?}"
+        );
+
+        let body = test::read_body(response).await;
+        assert!(
+            String::from_utf8_lossy(&body).contains("application/cbor"),
+            "{body:?}"
+        );
+    }
+
+    assert_eq!(mock.create_user.call_count(), 1);
+    assert_eq!(mock.create_users.call_count(), 1);
+}
@@ -0,0 +1,103 @@
+use actix_web::{http::StatusCode, test, web, App};
+use serde_json::Value;
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/request_body_raw.yaml",
+        mock = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{mock::MockApiService, NdJsonConfig};
+
+fn events(body: &'static str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/events")
+        .insert_header(("Content-Type", "application/x-ndjson"))
+        .set_payload(body)
+}
+
+#[actix_web::test]
+async fn test_ndjson_body() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.ingest_events
+        .respond_with(|call| web::Json(call.body.len() as i64));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .app_data(NdJsonConfig { line_limit: 32 })
+            .service(api::make_scope::<MockApiService, MockApiService>()),
+    )
+    .await;
+
+    // Empty lines are skipped and the last line does not need the newline
+    let request = events("{\"name\": \"a\"}\n\n{\"name\": \"b\", \"value\": 2}").to_request();
+    let response: i64 = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response, 2);
+    assert_eq!(mock.ingest_events.calls()[0].body[1].value, Some(2));
+
+    // Line longer than the limit of the config
+    let request =
+        events("{\"name\": \"a\"}\n{\"name\": \"a very long name of the event\"}\n").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Invalid item is reported by its index
+    let request = events("{\"name\": \"a\"}\n{\"value\": 1}\n").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["errors"][0]["field"], "body[1].name");
+
+    let request = test::TestRequest::post()
+        .uri("/events")
+        .set_json(serde_json::json!([{"name": "a"}]))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    assert_eq!(mock.ingest_events.call_count(), 1);
+}
+
+#[actix_web::test]
+async fn test_binary_body_max_length() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.upload_thumbnail.respond_with(|_| web::Json(true));
+    mock.upload
+        .respond_with(|call| web::Json(call.body.len() as i64));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .service(api::make_scope::<MockApiService, MockApiService>()),
+    )
+    .await;
+
+    for (size, status) in [
+        (65_536, StatusCode::OK),
+        (65_537, StatusCode::PAYLOAD_TOO_LARGE),
+    ] {
+        let request = test::TestRequest::post()
+            .uri("/thumbnail")
+            .insert_header(("Content-Type", "image/png"))
+            .set_payload(vec![0u8; size])
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), status, "{size}");
+    }
+
+    assert_eq!(mock.upload_thumbnail.call_count(), 1);
+
+    // Streamed body without maxLength is not limited by the payload config
+    let request = test::TestRequest::post()
+        .uri("/upload")
+        .insert_header(("Content-Type", "application/octet-stream"))
+        .set_payload(vec![0u8; 300_000])
+        .to_request();
+    let response: i64 = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response, 300_000);
+}
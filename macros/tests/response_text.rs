@@ -0,0 +1,59 @@
+use actix_web::{http::StatusCode, test, web, App};
+use async_trait::async_trait;
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/response_text.yaml",
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{ApiService, Detailed, GetReportError, GetReportPath};
+
+/// Reports rendered as plain text
+struct Reports;
+
+#[async_trait(?Send)]
+impl ApiService<()> for Reports {
+    async fn get_report(
+        _data: web::Data<()>,
+        path: web::Path<GetReportPath>,
+    ) -> Result<String, Detailed<GetReportError>> {
+        if path.report_id != "daily" {
+            apibail!(GetReportError::ReportNotFound, "No report of the id");
+        }
+
+        Ok("2 pets adopted".to_string())
+    }
+
+    async fn get_version(_data: web::Data<()>) -> String {
+        "1.0".to_string()
+    }
+}
+
+#[actix_web::test]
+async fn test_response_text() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(()))
+            .service(api::make_scope::<Reports, ()>()),
+    )
+    .await;
+
+    for (uri, expected) in [("/reports/daily", "2 pets adopted"), ("/version", "1.0")] {
+        let request = test::TestRequest::get().uri(uri).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(test::read_body(response).await, expected);
+    }
+
+    let request = test::TestRequest::get().uri("/reports/weekly").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
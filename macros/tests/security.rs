@@ -0,0 +1,193 @@
+use actix_web::{http::StatusCode, test, web, App};
+use async_trait::async_trait;
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/security.yaml",
+        mock = true,
+        split_by_tag = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::{
+    mock::MockApiService, ApiKey, AuthError, Authenticator, BasicAuth, BearerAuth, PetstoreAuth,
+    QueryKey, Session,
+};
+
+/// Accepts the `good` token with any scopes, and the `reader` one only without scopes
+struct Tokens;
+
+fn check_token(token: &str, scopes: &[&str]) -> Result<(), AuthError> {
+    match (token, scopes.is_empty()) {
+        ("good", _) | ("reader", true) => Ok(()),
+        ("reader", false) => Err(AuthError::Forbidden("Read only".to_string())),
+        _ => Err(AuthError::Unauthorized("Unknown token".to_string())),
+    }
+}
+
+#[async_trait(?Send)]
+impl Authenticator<MockApiService> for Tokens {
+    async fn authenticate_bearer_auth(
+        _data: &web::Data<MockApiService>,
+        credentials: &BearerAuth,
+        scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        check_token(&credentials.token, scopes)
+    }
+
+    async fn authenticate_basic_auth(
+        _data: &web::Data<MockApiService>,
+        credentials: &BasicAuth,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        match (credentials.username.as_str(), credentials.password.as_str()) {
+            ("admin", "secret") => Ok(()),
+            _ => Err(AuthError::Unauthorized("Wrong password".to_string())),
+        }
+    }
+
+    async fn authenticate_api_key(
+        _data: &web::Data<MockApiService>,
+        credentials: &ApiKey,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        check_token(&credentials.key, &[])
+    }
+
+    async fn authenticate_query_key(
+        _data: &web::Data<MockApiService>,
+        credentials: &QueryKey,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        check_token(&credentials.key, &[])
+    }
+
+    async fn authenticate_session(
+        _data: &web::Data<MockApiService>,
+        credentials: &Session,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        check_token(&credentials.key, &[])
+    }
+
+    async fn authenticate_petstore_auth(
+        _data: &web::Data<MockApiService>,
+        credentials: &PetstoreAuth,
+        scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        assert_eq!(scopes, ["write:pets", "read:pets"]);
+        check_token(&credentials.token, scopes)
+    }
+}
+
+fn challenge(response: &actix_web::dev::ServiceResponse) -> Option<&str> {
+    response
+        .headers()
+        .get("WWW-Authenticate")
+        .map(|value| value.to_str().unwrap())
+}
+
+#[actix_web::test]
+async fn test_authentication() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.list_pets.respond_with(|_| web::Json(vec![]));
+    mock.get_pet
+        .respond_with(|call| web::Json(call.path.pet_id.clone()));
+    mock.delete_pet.respond_with(|_| web::Json(true));
+    mock.add_pet.respond_with(|_| web::Json(true));
+    mock.export.respond_with(|_| web::Json("pets".to_string()));
+
+    let app = test::init_service(App::new().app_data(mock.clone()).service(api::make_scope::<
+        MockApiService,
+        Tokens,
+        MockApiService,
+    >()))
+    .await;
+
+    // Rejected callers are challenged with the schemes of the operation,
+    // before invalid arguments are reported
+    let requests = [
+        (test::TestRequest::get().uri("/pets/ab"), Some("Bearer")),
+        (
+            test::TestRequest::get()
+                .uri("/pets/rex")
+                .insert_header(("Authorization", "Bearer bad")),
+            Some("Bearer"),
+        ),
+        (
+            test::TestRequest::delete()
+                .uri("/pets/rex")
+                .insert_header(("Authorization", "Basic YWRtaW46d3Jvbmc=")),
+            Some("Basic realm=\"BasicAuth\", Bearer"),
+        ),
+        (test::TestRequest::get().uri("/export?api_key=bad"), None),
+        (test::TestRequest::get().uri("/export"), None),
+    ];
+
+    for (request, expected) in requests {
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(challenge(&response), expected);
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "application/problem+json"
+        );
+    }
+
+    // Forbidden callers are not challenged
+    let request = test::TestRequest::post()
+        .uri("/pets")
+        .insert_header(("Authorization", "Bearer reader"))
+        .set_json("rex")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(challenge(&response), None);
+
+    assert_eq!(mock.get_pet.call_count(), 0);
+    assert_eq!(mock.delete_pet.call_count(), 0);
+    assert_eq!(mock.add_pet.call_count(), 0);
+    assert_eq!(mock.export.call_count(), 0);
+
+    // Accepted credentials are passed to the operation
+    let request = test::TestRequest::get()
+        .uri("/pets/rex")
+        .insert_header(("Authorization", "Bearer reader"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(mock.get_pet.calls()[0].auth.token, "reader");
+
+    let request = test::TestRequest::delete()
+        .uri("/pets/rex")
+        .insert_header(("Authorization", "Basic YWRtaW46c2VjcmV0"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let auth = &mock.delete_pet.calls()[0].auth;
+    assert_eq!(auth.basic_auth.as_ref().unwrap().username, "admin");
+    assert_eq!(auth.bearer_auth, None);
+
+    let request = test::TestRequest::post()
+        .uri("/pets")
+        .insert_header(("Authorization", "Bearer good"))
+        .set_json("rex")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/export?api_key=good")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Operation allowing anonymous calls gets no credentials without them
+    let request = test::TestRequest::get().uri("/pets").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(mock.list_pets.calls()[0].auth, None);
+}
@@ -0,0 +1,122 @@
+use actix_web::{
+    http::{Method, StatusCode},
+    middleware::DefaultHeaders,
+    test, web, App,
+};
+use async_trait::async_trait;
+
+#[macro_use]
+pub mod tags {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/tags.yaml",
+        mock = true,
+        split_by_tag = true,
+        metrics = "none",
+        module_path = "tags",
+    );
+}
+
+use tags::{mock::MockApiService, GetPetPath, PetsApi};
+
+/// Pets operations of the mock, marking responses with the hooks which ran for them
+struct Pets;
+
+#[async_trait(?Send)]
+impl PetsApi<MockApiService> for Pets {
+    async fn list_pets(data: web::Data<MockApiService>) -> web::Json<Vec<String>> {
+        <MockApiService as PetsApi<_>>::list_pets(data).await
+    }
+
+    async fn get_pet(
+        data: web::Data<MockApiService>,
+        path: web::Path<GetPetPath>,
+    ) -> web::Json<String> {
+        <MockApiService as PetsApi<_>>::get_pet(data, path).await
+    }
+
+    fn wrap_route(operation_id: &str, tags: &[&str], route: actix_web::Route) -> actix_web::Route {
+        route.wrap(
+            DefaultHeaders::new()
+                .add(("x-route", operation_id.to_string()))
+                .add(("x-route-tags", tags.join(","))),
+        )
+    }
+
+    fn configure_tag(tag: &str, scope: actix_web::Scope, config: &mut web::ServiceConfig) {
+        config.service(scope.wrap(DefaultHeaders::new().add(("x-tag", tag.to_string()))));
+    }
+}
+
+fn header(response: &actix_web::dev::ServiceResponse, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[actix_web::test]
+async fn test_tag_hooks() {
+    let mock = web::Data::new(MockApiService::default());
+    mock.list_pets
+        .respond_with(|_| web::Json(vec!["rex".to_string()]));
+    mock.get_pet
+        .respond_with(|call| web::Json(call.path.pet_id.clone()));
+    mock.list_orders.respond_with(|_| web::Json(vec![]));
+    mock.place_order.respond_with(|_| web::Json(true));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .service(tags::make_scope::<Pets, MockApiService, MockApiService>()),
+    )
+    .await;
+
+    // Operations of the pets tag run through both hooks of Pets
+    for (uri, operation_id) in [("/pets", "list_pets"), ("/v1/pets/rex", "get_pet")] {
+        let request = test::TestRequest::get().uri(uri).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+        assert_eq!(header(&response, "x-tag").as_deref(), Some("pets"), "{uri}");
+        assert_eq!(header(&response, "x-route").as_deref(), Some(operation_id));
+        assert_eq!(header(&response, "x-route-tags").as_deref(), Some("pets"));
+    }
+
+    // Operations of the store tag, including the one also tagged pets, keep the default hooks
+    let request = test::TestRequest::get().uri("/orders").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header(&response, "x-tag"), None);
+    assert_eq!(header(&response, "x-route"), None);
+
+    let request = test::TestRequest::post()
+        .uri("/orders")
+        .set_json("rex")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header(&response, "x-tag"), None);
+    assert_eq!(header(&response, "x-route"), None);
+
+    assert_eq!(mock.list_pets.call_count(), 1);
+    assert_eq!(mock.get_pet.calls()[0].path.pet_id, "rex");
+    assert_eq!(mock.list_orders.call_count(), 1);
+    assert_eq!(mock.place_order.call_count(), 1);
+
+    // Methods the spec does not declare are not routed to any tag
+    for (method, uri) in [(Method::DELETE, "/pets"), (Method::PUT, "/orders")] {
+        let request = test::TestRequest::default()
+            .method(method)
+            .uri(uri)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(
+            matches!(
+                response.status(),
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            ),
+            "{uri}: {}",
+            response.status()
+        );
+        assert_eq!(header(&response, "x-tag"), None);
+    }
+}
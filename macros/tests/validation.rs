@@ -0,0 +1,72 @@
+use actix_web::{http::StatusCode, test, web, App};
+use serde_json::{json, Value};
+
+#[macro_use]
+pub mod api {
+    cargo_actix_openapi_macros::openapi!(
+        "../tests/openapi/validation.yaml",
+        mock = true,
+        document_errors = true,
+        metrics = "none",
+        module_path = "api",
+    );
+}
+
+use api::mock::MockApiService;
+
+const USER: &str = "/users/67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+#[actix_web::test]
+async fn test_extractor_errors() {
+    let mock = web::Data::new(MockApiService::default());
+
+    let app = test::init_service(
+        App::new()
+            .app_data(mock.clone())
+            .service(api::make_scope::<MockApiService, MockApiService>()),
+    )
+    .await;
+
+    // Every extractor failure is a 422 naming the invalid argument
+    let requests = [
+        (
+            test::TestRequest::post()
+                .uri(USER)
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload("{\"name\": "),
+            "body",
+        ),
+        (
+            test::TestRequest::post().uri(USER).set_json(json!({
+                "email": "rex@example.com",
+                "tags": ["dog"],
+                "address": {"city": "Prague"}
+            })),
+            "body.name",
+        ),
+        (test::TestRequest::get().uri("/users?limit=ten"), "query"),
+    ];
+
+    for (request, field) in requests {
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{field}"
+        );
+
+        let body: Value = test::read_body_json(response).await;
+        assert_eq!(body["errors"][0]["field"], field, "{body}");
+        assert!(body["errors"][0]["reason"].is_string(), "{body}");
+    }
+
+    assert_eq!(mock.update_user.call_count(), 0);
+    assert_eq!(mock.list_users.call_count(), 0);
+
+    // Served spec documents the error response
+    let request = test::TestRequest::get().uri("/openapi.yaml").to_request();
+    let body = test::call_and_read_body(&app, request).await;
+    let spec = String::from_utf8(body.to_vec()).unwrap();
+    assert!(spec.contains("'422':"), "{spec}");
+    assert!(spec.contains("$ref: '#/components/schemas/ValidationErrors'"));
+}
@@ -0,0 +1,330 @@
+//! Generation of the api from build scripts
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    generator::{self, ApiOptions, OpenapiWithMeta},
+    specs::{self, is_doc_page, DEFAULT_TITLE},
+};
+
+/// Absolute path of the file, included by the generated code wherever it is written
+fn absolute_path(path: &Path) -> Result<String> {
+    let absolute = fs::canonicalize(path)
+        .with_context(|| format!("Could not find {}", path.to_string_lossy()))?;
+
+    let Some(absolute) = absolute.to_str() else {
+        bail!(
+            "Could not represent path {} as string",
+            absolute.to_string_lossy()
+        )
+    };
+
+    Ok(absolute.to_string())
+}
+
+/// Tells cargo to run the build script again when the path changes
+fn rerun_if_changed(output: &mut impl Write, path: &Path) -> Result<()> {
+    writeln!(output, "cargo:rerun-if-changed={}", path.to_string_lossy())
+        .context("Could not print cargo instruction")
+}
+
+/// Generates the api module from `build.rs`, to be included with
+/// `include!(concat!(env!("OUT_DIR"), "/api.rs"))`
+///
+/// ```no_run
+/// cargo_actix_openapi::Builder::new()
+///     .spec_dir("src/server/static")
+///     .module_path("server::api")
+///     .generate()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    spec_dir: Option<PathBuf>,
+    specs: Vec<PathBuf>,
+    exclude: Vec<String>,
+    docs: Option<PathBuf>,
+    title: Option<String>,
+    out_path: Option<PathBuf>,
+    module_path: Option<String>,
+    templates: Option<PathBuf>,
+    options: ApiOptions,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Directory with `openapi*.yaml` specs and optional `docs.html`, as taken by the command line
+    pub fn spec_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spec_dir = Some(dir.into());
+        self
+    }
+
+    /// Spec file or glob pattern to generate the api from, along with the specs found in `spec_dir`
+    pub fn spec(mut self, path: impl Into<PathBuf>) -> Self {
+        self.specs.push(path.into());
+        self
+    }
+
+    /// Skip spec files matching the glob pattern, as taken by `--exclude`
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Docs page served by the api. When neither given nor found in `spec_dir`,
+    /// the default page is embedded into the generated module
+    pub fn docs(mut self, path: impl Into<PathBuf>) -> Self {
+        self.docs = Some(path.into());
+        self
+    }
+
+    /// Application name shown on the embedded docs page
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// File to write the generated module into, `api.rs` in `OUT_DIR` by default
+    pub fn out_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.out_path = Some(path.into());
+        self
+    }
+
+    /// Path of the module including the generated file in its crate, like `server::api`
+    pub fn module_path(mut self, path: &str) -> Self {
+        self.module_path = Some(path.to_string());
+        self
+    }
+
+    /// Directory of templates overriding the built-in ones, as taken by `--templates`
+    pub fn templates(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.templates = Some(dir.into());
+        self
+    }
+
+    pub fn options(mut self, options: ApiOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Writes the generated module, if it changed, and returns its path.
+    /// Prints `cargo:rerun-if-changed` for the spec directory and every file used,
+    /// including the files referenced by the specs
+    pub fn generate(self) -> Result<PathBuf> {
+        self.generate_with(&mut std::io::stdout())
+    }
+
+    /// Generates printing the cargo instructions into the output
+    fn generate_with(self, output: &mut impl Write) -> Result<PathBuf> {
+        let out_path = match self.out_path {
+            Some(path) => path,
+            None => {
+                let out_dir = env::var_os("OUT_DIR")
+                    .context("OUT_DIR is not set, pass out_path when not run by build script")?;
+                PathBuf::from(out_dir).join("api.rs")
+            }
+        };
+
+        let mut docs_path = self.docs;
+
+        if let Some(dir) = &self.spec_dir {
+            rerun_if_changed(output, dir)?;
+
+            let entries = fs::read_dir(dir)
+                .with_context(|| format!("Could not read {}", dir.to_string_lossy()))?;
+
+            for entry in entries {
+                let path = entry?.path();
+
+                if path.is_file() && is_doc_page(&path) && docs_path.is_none() {
+                    docs_path = Some(path);
+                }
+            }
+        }
+
+        let patterns: Vec<String> = self
+            .specs
+            .iter()
+            .map(|x| x.to_string_lossy().to_string())
+            .collect();
+
+        let spec_paths = specs::select_specs(self.spec_dir.as_deref(), &patterns, &self.exclude)?;
+
+        let mut specs = Vec::new();
+        let mut versions = HashMap::new();
+
+        for path in &spec_paths {
+            rerun_if_changed(output, path)?;
+
+            for file in specs::referenced_files(path) {
+                rerun_if_changed(output, &file)?;
+            }
+
+            let (content, _) = specs::read_spec(path, &mut versions)?;
+
+            specs.push(OpenapiWithMeta {
+                content,
+                path: absolute_path(path)?,
+            });
+        }
+
+        let mut options = self.options;
+        options.included = true;
+
+        if let Some(path) = &self.module_path {
+            options.module_path = Some(generator::parse_module_path(path)?);
+        }
+
+        if let Some(dir) = self.templates {
+            options.templates = Some(dir);
+        }
+
+        if let Some(dir) = &options.templates {
+            rerun_if_changed(output, dir)?;
+
+            for path in generator::custom_templates(dir)? {
+                rerun_if_changed(output, &path)?;
+            }
+        }
+
+        let docs_path = match &docs_path {
+            Some(path) => {
+                rerun_if_changed(output, path)?;
+                absolute_path(path)?
+            }
+            None => {
+                let title = self.title.as_deref().unwrap_or(DEFAULT_TITLE);
+                options.docs_html =
+                    Some(generator::generate_docs(title).context("Could not render docs page")?);
+                "docs.html".to_string()
+            }
+        };
+
+        let (_, generated) = generator::generate_api(&docs_path, &specs, &options)?;
+
+        // Keep the file untouched when nothing changed, so that it is not recompiled
+        if fs::read_to_string(&out_path).ok().as_deref() != Some(generated.as_str()) {
+            fs::write(&out_path, generated)
+                .with_context(|| format!("Could not write into {}", out_path.to_string_lossy()))?;
+        }
+
+        Ok(out_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spec directory with both versions of the mixed api, a draft of the second one
+    /// and the docs page
+    fn spec_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let specs = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/openapi");
+
+        for (source, target) in [
+            ("mixed_api_v1.yaml", "openapi_v1.yaml"),
+            ("mixed_api_v2.yaml", "openapi_v2.yaml"),
+            ("mixed_api_v2.yaml", "openapi_draft.yaml"),
+        ] {
+            fs::copy(specs.join(source), dir.path().join(target)).unwrap();
+        }
+
+        fs::write(dir.path().join("docs.html"), "").unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_generate() {
+        let dir = spec_dir();
+        let mut output = Vec::new();
+
+        let out_path = Builder::new()
+            .spec_dir(dir.path())
+            .exclude("*draft*")
+            .out_path(dir.path().join("api.rs"))
+            .module_path("server::api")
+            .generate_with(&mut output)
+            .unwrap();
+
+        let mut rerun = vec![dir.path().to_path_buf()];
+
+        for name in ["openapi_v1.yaml", "openapi_v2.yaml", "docs.html"] {
+            rerun.push(dir.path().join(name));
+        }
+
+        let rerun: Vec<_> = rerun
+            .iter()
+            .map(|x| format!("cargo:rerun-if-changed={}", x.display()))
+            .collect();
+
+        assert_eq!(String::from_utf8(output).unwrap(), rerun.join("\n") + "\n");
+
+        let generated = fs::read_to_string(out_path).unwrap();
+
+        assert!(generated.contains("pub fn make_scope"));
+        assert!(generated.contains(&absolute_path(&dir.path().join("openapi_v2.yaml")).unwrap()));
+        assert!(!generated.contains("#![allow"));
+        assert!(generated.contains("#[allow(unused_imports, dead_code, unused_variables)]"));
+    }
+
+    #[test]
+    fn test_generate_referenced() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path().join("common");
+        let spec = dir.path().join("openapi.yaml");
+
+        fs::create_dir(&common).unwrap();
+        fs::write(common.join("pets.yaml"), "Pet: {}\n").unwrap();
+
+        let content = fs::read_to_string(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/openapi/helloworld.yaml"),
+        )
+        .unwrap();
+
+        fs::write(
+            &spec,
+            content + "x-pets:\n  $ref: 'common/pets.yaml#/Pet'\n",
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+
+        Builder::new()
+            .spec(&spec)
+            .out_path(dir.path().join("api.rs"))
+            .generate_with(&mut output)
+            .unwrap();
+
+        let referenced = fs::canonicalize(common.join("pets.yaml")).unwrap();
+
+        let rerun = format!("cargo:rerun-if-changed={}\n", referenced.display());
+
+        assert!(String::from_utf8(output).unwrap().contains(&rerun));
+    }
+
+    #[test]
+    fn test_duplicate_major_version() {
+        let dir = spec_dir();
+
+        let error = Builder::new()
+            .spec_dir(dir.path())
+            .out_path(dir.path().join("api.rs"))
+            .generate_with(&mut Vec::new())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("same major version 2"));
+        assert!(!dir.path().join("api.rs").exists());
+    }
+}
@@ -0,0 +1,129 @@
+//! Options of the command line read from configuration files
+
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::generator::Metrics;
+
+/// Configuration file read from the spec directory
+pub const CONFIG_FILE: &str = "actix-openapi.toml";
+
+/// Options of the command line named by their long names, with paths relative to the file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub spec_dir: Option<PathBuf>,
+    pub out_path: Option<PathBuf>,
+    pub spec: Vec<String>,
+    pub exclude: Vec<String>,
+    pub client: Option<PathBuf>,
+    pub mock: Option<bool>,
+    pub document_errors: Option<bool>,
+    pub metrics: Option<Metrics>,
+    pub tracing: Option<bool>,
+    pub extractor: Vec<String>,
+    pub derive: Vec<String>,
+    pub type_map: HashMap<String, String>,
+    pub split_by_tag: Option<bool>,
+    pub module_path: Option<String>,
+    pub yes: Option<bool>,
+    pub title: Option<String>,
+    pub no_docs: Option<bool>,
+    pub templates: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    metadata: Option<Metadata>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    #[serde(rename = "actix-openapi")]
+    actix_openapi: Option<Config>,
+}
+
+impl Config {
+    fn relative_to(mut self, dir: &Path) -> Self {
+        for path in [
+            &mut self.spec_dir,
+            &mut self.out_path,
+            &mut self.client,
+            &mut self.templates,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *path = dir.join(&path);
+        }
+
+        // Patterns without `/` match file names
+        let patterns = self.spec.iter_mut().chain(
+            self.exclude
+                .iter_mut()
+                .filter(|pattern| pattern.contains('/')),
+        );
+
+        for pattern in patterns {
+            *pattern = dir.join(&pattern).to_string_lossy().to_string();
+        }
+
+        self
+    }
+
+    /// Reads `actix-openapi.toml` from the directory, if there is one
+    pub fn from_dir(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(CONFIG_FILE);
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = read_to_string(&path)
+            .with_context(|| format!("Could not read {}", path.to_string_lossy()))?;
+
+        let config: Config = toml::from_str(&content)
+            .with_context(|| format!("Could not parse {}", path.to_string_lossy()))?;
+
+        Ok(Some(config.relative_to(dir)))
+    }
+
+    /// Path of the `Cargo.toml` closest to the directory
+    pub fn manifest_path(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|x| x.join("Cargo.toml"))
+            .find(|x| x.is_file())
+    }
+
+    /// Reads `[package.metadata.actix-openapi]` of the `Cargo.toml` closest to the directory
+    pub fn from_manifest(dir: &Path) -> Result<Option<Self>> {
+        let Some(path) = Self::manifest_path(dir) else {
+            return Ok(None);
+        };
+        let manifest_dir = path.parent().unwrap_or(dir);
+
+        let content = read_to_string(&path)
+            .with_context(|| format!("Could not read {}", path.to_string_lossy()))?;
+
+        let manifest: Manifest = toml::from_str(&content)
+            .with_context(|| format!("Could not parse {}", path.to_string_lossy()))?;
+
+        let config = manifest
+            .package
+            .and_then(|x| x.metadata)
+            .and_then(|x| x.actix_openapi);
+
+        Ok(config.map(|x| x.relative_to(manifest_dir)))
+    }
+}
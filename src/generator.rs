@@ -1,16 +1,29 @@
+use std::{
+    collections::HashSet,
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
 use indexmap::IndexMap;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use convert_case::{Case, Casing};
 
+mod format;
 mod models;
+mod security;
+mod served;
 mod templates;
+mod validation;
 
 use models::to_rust_module;
 
 use self::models::{
     types::{
-        DefaultProvider, OperationPath, RApiErr, REnum, RStruct, RustOperation, StaticHtmlPath,
-        StaticRedirect, StaticStr, StaticStringPath,
+        to_rust_identifier, BodyFormat, Constraints, DefaultProvider, InlineType, OperationPath,
+        RApiErr, REnum, RMediaTypes, RStruct, RustOperation, StaticHtmlPath, StaticRedirect,
+        StaticStr, StaticStringPath,
     },
     OpenApiWithPath,
 };
@@ -102,6 +115,11 @@ fn convert_error(name: &str, err: &RApiErr) -> templates::RustError {
             title: variant.name.clone(),
             status: variant.code.clone(),
             display: variant.detail.clone(),
+            problem_type: format!(
+                "urn:problem:{}:{}",
+                name.to_case(Case::Kebab),
+                variant.name.to_case(Case::Kebab)
+            ),
         })
     }
 
@@ -112,43 +130,365 @@ fn convert_error(name: &str, err: &RApiErr) -> templates::RustError {
     }
 }
 
-fn convert_method(name: &str, op: &RustOperation) -> templates::RustMethod {
+fn convert_media_types(name: &str, media: &RMediaTypes) -> templates::RustMediaTypes {
+    let mut media_types = Vec::new();
+
+    for media_type in &media.media_types {
+        media_types.push(templates::RustMediaType {
+            media_type: media_type.media_type.clone(),
+            format: media_type.format.to_string(),
+        })
+    }
+
+    templates::RustMediaTypes {
+        title: name.to_string(),
+        media_types,
+    }
+}
+
+/// Recorded type of the handler argument and expression converting argument into it
+fn record_arg(name: &str, type_: &InlineType) -> (String, String) {
+    match type_ {
+        InlineType::Path(item)
+        | InlineType::Query(item)
+        | InlineType::Json(item)
+        | InlineType::Negotiated(item, _) => (item.to_string(), format!("{name}.into_inner()")),
+        InlineType::NdJson(item) => (
+            format!("Vec<{item}>"),
+            format!("futures::TryStreamExt::try_collect({name}).await?"),
+        ),
+        InlineType::Payload => (
+            "web::Bytes".to_string(),
+            format!("{name}.to_bytes().await?"),
+        ),
+        InlineType::Option(inner) => {
+            let (record_type, record) = record_arg(name, inner);
+            (
+                format!("Option<{record_type}>"),
+                format!("match {name} {{ Some({name}) => Some({record}), None => None }}"),
+            )
+        }
+        _ => (type_.to_string(), name.to_string()),
+    }
+}
+
+/// Argument streams the body to the handler, so reading it can fail after the extraction
+fn is_streamed(type_: &InlineType) -> bool {
+    match type_ {
+        InlineType::NdJson(_) | InlineType::Payload => true,
+        InlineType::Option(inner) => is_streamed(inner),
+        _ => false,
+    }
+}
+
+fn convert_method_arg(
+    name: &str,
+    type_: &InlineType,
+    constraints: &Constraints,
+    validated: &HashSet<String>,
+) -> templates::RustMethodArg {
+    let (record_type, record) = record_arg(name, type_);
+
+    templates::RustMethodArg {
+        name: name.to_string(),
+        type_: type_.to_string(),
+        record_type,
+        record,
+        validate: validation::arg_validation(name, type_, constraints, validated),
+        validate_stream: validation::stream_validation(name, type_, constraints, validated),
+    }
+}
+
+fn convert_extractor(extractor: &Extractor) -> templates::RustExtractor {
+    templates::RustExtractor {
+        name: extractor.name.clone(),
+        type_: extractor.type_.clone(),
+    }
+}
+
+/// Tag of the operations without any
+const DEFAULT_TAG: &str = "default";
+
+/// Type parameter of `make_scope` implementing `Authenticator` when split by tags
+const AUTHENTICATOR: &str = "AuthImpl";
+
+/// Trait of the api service declaring the operation: the one of its first tag
+/// when split by tags, otherwise `ApiService` with all of the operations
+fn convert_service(op: &RustOperation, split_by_tag: bool) -> templates::RustService {
+    if !split_by_tag {
+        return templates::RustService {
+            doc: None,
+            title: "ApiService".to_string(),
+            implementor: "T".to_string(),
+        };
+    }
+
+    let doc = match op.tags.first() {
+        Some(tag) => format!("Operations tagged `{tag}` in the spec"),
+        None => "Operations without tags in the spec".to_string(),
+    };
+
+    let tag = op.tags.first().map_or(DEFAULT_TAG, String::as_str);
+    let name = to_rust_identifier(tag, Case::UpperCamel);
+
+    templates::RustService {
+        doc: Some(doc),
+        title: format!("{name}Api"),
+        implementor: format!("{name}Impl"),
+    }
+}
+
+fn convert_method(
+    name: &str,
+    op: &RustOperation,
+    validated: &HashSet<String>,
+    options: &ApiOptions,
+) -> templates::RustMethod {
     let mut args = Vec::new();
 
     if let Some(param) = &op.param_path {
-        args.push(templates::RustMethodArg {
-            name: "path".to_string(),
-            type_: param.to_string(),
-        })
+        args.push(convert_method_arg(
+            "path",
+            param,
+            &Constraints::default(),
+            validated,
+        ))
     }
 
     if let Some(param) = &op.param_query {
-        args.push(templates::RustMethodArg {
-            name: "query".to_string(),
-            type_: param.to_string(),
-        })
+        args.push(convert_method_arg(
+            "query",
+            param,
+            &Constraints::default(),
+            validated,
+        ))
     }
 
     if let Some(param) = &op.param_body {
-        args.push(templates::RustMethodArg {
-            name: "body".to_string(),
-            type_: param.to_string(),
-        })
+        args.push(convert_method_arg(
+            "body",
+            param,
+            &op.body_constraints,
+            validated,
+        ))
     }
 
+    let call_type = to_rust_identifier(name, Case::UpperCamel);
+    let service = convert_service(op, options.split_by_tag);
+
+    // Checked handler has its own type parameter implementing `Authenticator` when split by tags
+    let authenticator = match options.split_by_tag {
+        true => "A",
+        false => "T",
+    };
+
+    // Errors of reading the streamed body are propagated by the handler as the response
+    let fallible = op.param_body.as_ref().is_some_and(is_streamed);
+    let response_type = op.response.to_string();
+    let return_type = match fallible {
+        true => format!("Result<{response_type}, actix_web::Error>"),
+        false => response_type.clone(),
+    };
+
     templates::RustMethod {
         operation_id: name.to_string(),
-        response_type: op.response.to_string(),
+        response_type,
+        return_type,
+        fallible,
         doc: op.doc.clone(),
+        validated: args.iter().any(|arg| arg.validate.is_some()),
+        validated_stream: args.iter().any(|arg| arg.validate_stream.is_some()),
+        auth: security::method_auth(name, op, authenticator),
         args,
+        extractors: options.extractors.iter().map(convert_extractor).collect(),
+        call_type: format!("{call_type}Call"),
+        service: service.title,
+        implementor: service.implementor,
+        tags: op.tags.clone(),
+    }
+}
+
+/// Traits of the api service declaring the methods, in order of their first method
+fn collect_services(
+    operations: &IndexMap<String, RustOperation>,
+    methods: &[templates::RustMethod],
+    split_by_tag: bool,
+) -> Vec<templates::RustService> {
+    let mut services: Vec<templates::RustService> = Vec::new();
+
+    for method in methods {
+        if services.iter().any(|x| x.title == method.service) {
+            continue;
+        }
+
+        if let Some(op) = operations.get(&method.operation_id) {
+            services.push(convert_service(op, split_by_tag));
+        }
+    }
+
+    services
+}
+
+/// Version of the api encoded in path prefix like `/v2/...`
+fn path_version(path: &str) -> Option<usize> {
+    let rest = path.strip_prefix("/v")?;
+    let (version, _) = rest.split_once('/')?;
+    version.parse().ok()
+}
+
+/// Path the client calls operation with
+/// Unprefixed paths only exist for version 1, so the latest versioned path is preferred
+fn client_path<'a>(name: &str, paths: &'a [OperationPath]) -> Option<&'a OperationPath> {
+    paths
+        .iter()
+        .filter(|x| x.operation == name)
+        .max_by_key(|x| path_version(&x.path))
+}
+
+fn unwrap_option(type_: &InlineType) -> (&InlineType, bool) {
+    match type_ {
+        InlineType::Option(inner) => (inner.as_ref(), true),
+        _ => (type_, false),
     }
 }
 
-fn convert_method_path(path: &OperationPath) -> templates::MethodPath {
+fn convert_client_body(
+    body: &InlineType,
+    media_type: &Option<String>,
+) -> Result<templates::ClientBody> {
+    let (body, optional) = unwrap_option(body);
+
+    let Some(media_type) = media_type else {
+        bail!("Request body media type is unknown")
+    };
+
+    let (encoding, type_) = match body {
+        InlineType::Json(item) => ("json", format!("&{item}")),
+        InlineType::Negotiated(item, _) => {
+            let encoding = match BodyFormat::from_media_type(media_type) {
+                Some(BodyFormat::Json) => "json",
+                Some(BodyFormat::Cbor) => "cbor",
+                Some(BodyFormat::MsgPack) => "msgpack",
+                None => bail!("Unsupported media type {media_type}"),
+            };
+            (encoding, format!("&{item}"))
+        }
+        InlineType::NdJson(item) => ("ndjson", format!("&[{item}]")),
+        InlineType::Payload | InlineType::Bytes(_) | InlineType::String => {
+            ("raw", "impl Into<reqwest::Body>".to_string())
+        }
+        _ => bail!("Body type {body} is not supported by client"),
+    };
+
+    let arg_type = match optional {
+        true => format!("Option<{type_}>"),
+        false => type_,
+    };
+
+    Ok(templates::ClientBody {
+        arg_type,
+        encoding: encoding.to_string(),
+        media_type: media_type.clone(),
+        optional,
+    })
+}
+
+fn convert_client_method(
+    name: &str,
+    op: &RustOperation,
+    path: &OperationPath,
+) -> Result<templates::ClientMethod> {
+    let path_type = match &op.param_path {
+        Some(InlineType::Path(inner)) => Some(inner.to_string()),
+        Some(other) => bail!("Unexpected path parameters type {other}"),
+        None => None,
+    };
+
+    let query_type = match &op.param_query {
+        Some(InlineType::Query(inner)) => Some(inner.to_string()),
+        Some(other) => bail!("Unexpected query parameters type {other}"),
+        None => None,
+    };
+
+    let body = match &op.param_body {
+        Some(body) => Some(convert_client_body(body, &op.body_media_type)?),
+        None => None,
+    };
+
+    let (success, error) = match &op.response {
+        InlineType::Result(ok, err) => (ok.as_ref(), Some(err.as_ref())),
+        other => (other, None),
+    };
+
+    let error_type = match error {
+        Some(InlineType::Detailed(inner)) => inner.to_string(),
+        Some(other) => bail!("Unexpected error type {other}"),
+        None => "std::convert::Infallible".to_string(),
+    };
+
+    // Responses other than json, like event streams and plain text, are returned as is
+    let (response_type, streaming) = match success {
+        InlineType::Json(inner) => (inner.to_string(), false),
+        _ => ("reqwest::Response".to_string(), true),
+    };
+
+    Ok(templates::ClientMethod {
+        operation_id: name.to_string(),
+        doc: op.doc.clone(),
+        method: path.method.to_string(),
+        path: path.path.clone(),
+        path_type,
+        query_type,
+        body,
+        response_type,
+        streaming,
+        error_type,
+    })
+}
+
+/// Length of the buffered binary body, which actix limits to 256 KiB by default
+fn payload_limit(operation: &RustOperation) -> Option<usize> {
+    match operation.param_body.as_ref()? {
+        InlineType::Bytes(limit) => Some(*limit),
+        InlineType::Option(inner) => match inner.as_ref() {
+            InlineType::Bytes(limit) => Some(*limit),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn convert_method_path(
+    path: &OperationPath,
+    operation: &RustOperation,
+    method: &templates::RustMethod,
+    split_by_tag: bool,
+) -> templates::MethodPath {
+    let handler = match method.validated || method.validated_stream || method.auth.is_some() {
+        true => {
+            let mut generics = vec![method.implementor.as_str()];
+            if split_by_tag && method.auth.is_some() {
+                generics.push(AUTHENTICATOR);
+            }
+            generics.push("S");
+
+            format!("checked_{}::<{}>", path.operation, generics.join(", "))
+        }
+        false => format!("{}::{}", method.implementor, path.operation),
+    };
+
     templates::MethodPath {
         operation_id: path.operation.clone(),
         path: path.path.clone(),
         method: path.method.to_string(),
+        handler,
+        implementor: method.implementor.clone(),
+        tags: method
+            .tags
+            .iter()
+            .map(|x| templates::quote_str(x))
+            .collect(),
+        payload_limit: payload_limit(operation),
     }
 }
 
@@ -156,6 +496,7 @@ fn convert_include(name: &str, value: &StaticStr) -> templates::StaticInclude {
     templates::StaticInclude {
         title: name.to_string(),
         file_path: value.path.clone(),
+        content: value.content.clone(),
     }
 }
 
@@ -185,7 +526,218 @@ pub struct OpenapiWithMeta {
     pub path: String,
 }
 
-pub fn generate_api(docs_path: &str, specs: &[OpenapiWithMeta]) -> Result<(String, String)> {
+/// Middleware collecting metrics of the api operations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metrics {
+    /// No metrics, `make_scope` takes no middleware
+    None,
+    /// `actix_web_prom::PrometheusMetrics` wrapping the whole api scope
+    #[default]
+    Prometheus,
+    /// Any `Transform` middleware wrapping every operation route
+    Middleware,
+}
+
+impl Metrics {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Metrics::None => "none",
+            Metrics::Prometheus => "prometheus",
+            Metrics::Middleware => "middleware",
+        }
+    }
+}
+
+/// Argument appended to every method of the api service, extracted from the request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extractor {
+    pub name: String,
+    /// Type implementing `FromRequest`, like `HttpRequest` or `web::ReqData<User>`
+    pub type_: String,
+}
+
+/// Names of the arguments the generated handlers already take
+const RESERVED_ARGS: [&str; 6] = ["data", "auth", "path", "query", "body", "req"];
+
+impl FromStr for Extractor {
+    type Err = anyhow::Error;
+
+    /// Parses `name=Type`, or just `Type` named by its last path segment in snake case
+    fn from_str(value: &str) -> Result<Self> {
+        let (name, type_) = match value.split_once('=') {
+            Some((name, type_)) => (name.trim().to_string(), type_.trim()),
+            None => {
+                let path = value.split('<').next().unwrap_or_default();
+                let segment = path.rsplit("::").next().unwrap_or_default().trim();
+                (segment.to_case(Case::Snake), value.trim())
+            }
+        };
+
+        let identifier = name.chars().next().is_some_and(|x| !x.is_ascii_digit())
+            && name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_');
+
+        if !identifier {
+            bail!("Extractor name {name:?} is not an identifier");
+        }
+
+        if RESERVED_ARGS.contains(&name.as_str()) {
+            bail!("Extractor name {name:?} is already used by the generated handlers");
+        }
+
+        if type_.is_empty() {
+            bail!("Extractor {name:?} has no type");
+        }
+
+        Ok(Extractor {
+            name,
+            type_: type_.to_string(),
+        })
+    }
+}
+
+/// Options of the api generation
+#[derive(Debug, Clone, Default)]
+pub struct ApiOptions {
+    /// Generate `mock::MockApiService` implementing the api service
+    pub mock: bool,
+    /// Document 422 response with invalid request arguments in the served specs
+    pub document_errors: bool,
+    /// Middleware collecting metrics passed to `make_scope`
+    pub metrics: Metrics,
+    /// Handle requests of every operation inside of a `tracing` span
+    pub tracing: bool,
+    /// Arguments appended to every method of the api service
+    pub extractors: Vec<Extractor>,
+    /// Declare operations in one trait per their first tag instead of `ApiService`
+    pub split_by_tag: bool,
+    /// Content of the docs page embedded instead of including the file at `docs_path`
+    pub docs_html: Option<String>,
+    /// Path of the generated module in its crate referenced by the exported macros,
+    /// empty for the crate root and `server::api` when not given
+    pub module_path: Option<String>,
+    /// Generate module to be `include!`d into another file, without inner attributes
+    pub included: bool,
+    /// Derives added to the structs and enums of the models, like `Hash` or `schemars::JsonSchema`
+    pub derives: Vec<String>,
+    /// Directory of templates overriding the built-in ones with the same file names
+    pub templates: Option<PathBuf>,
+    /// Rust types of the string, integer and number schemas by their `format`,
+    /// like `uuid` to `uuid::Uuid`, used in the models instead of the generated ones
+    pub type_map: IndexMap<String, String>,
+}
+
+/// Render the swagger docs page of the api titled with the application name
+pub fn generate_docs(title: &str) -> Result<String> {
+    templates::render_docs(templates::DocsPage {
+        title: title.to_string(),
+    })
+}
+
+/// Custom templates of the directory, failing on files not named as any of the built-in ones
+pub fn custom_templates(dir: &Path) -> Result<Vec<PathBuf>> {
+    templates::custom_templates(dir)
+}
+
+/// Write the built-in templates into the directory to be edited into custom templates.
+/// Existing files are not overwritten, as they may be custom templates already
+pub fn dump_templates(dir: &Path) -> Result<Vec<PathBuf>> {
+    create_dir_all(dir).with_context(|| format!("Could not create {}", dir.to_string_lossy()))?;
+
+    let mut paths = Vec::new();
+
+    for (name, _) in templates::TEMPLATES {
+        let path = dir.join(name);
+
+        if path.exists() {
+            bail!("Template {} already exists", path.to_string_lossy());
+        }
+
+        paths.push(path);
+    }
+
+    for ((_, content), path) in templates::TEMPLATES.iter().zip(&paths) {
+        write(path, format!("{}\n{content}", templates::version_line()))
+            .with_context(|| format!("Could not write {}", path.to_string_lossy()))?;
+    }
+
+    Ok(paths)
+}
+
+/// Module the generated file is expected to be in, as suggested by the readme
+const DEFAULT_MODULE_PATH: &str = "server::api";
+
+/// Parses path of the module in its crate like `server::api` or `crate::server::api`
+pub fn parse_module_path(value: &str) -> Result<String> {
+    let value = value.trim();
+    let value = match value {
+        "crate" => "",
+        _ => value.strip_prefix("crate::").unwrap_or(value),
+    };
+
+    if value.is_empty() {
+        return Ok(String::new());
+    }
+
+    for segment in value.split("::") {
+        let identifier = segment.chars().next().is_some_and(|x| !x.is_ascii_digit())
+            && segment
+                .chars()
+                .all(|x| x.is_ascii_alphanumeric() || x == '_');
+
+        if !identifier {
+            bail!("Module path segment {segment:?} is not an identifier");
+        }
+    }
+
+    Ok(value.to_string())
+}
+
+/// Parses mapping of the schema format to the rust type like `uuid=uuid::Uuid`
+pub fn parse_type_mapping(value: &str) -> Result<(String, String)> {
+    let Some((format, type_)) = value.split_once('=') else {
+        bail!("Type mapping {value:?} is not given as FORMAT=TYPE");
+    };
+
+    let (format, type_) = (format.trim(), type_.trim());
+
+    if format.is_empty() || type_.is_empty() {
+        bail!("Type mapping {value:?} has no format or no type");
+    }
+
+    Ok((format.to_string(), type_.to_string()))
+}
+
+/// Module path of the file from its location relative to the `src` directory of the crate,
+/// like `server::api` for `src/server/api.rs` or an empty path for `src/lib.rs`
+pub fn module_path(out_path: &Path) -> Option<String> {
+    let components: Vec<String> = out_path
+        .components()
+        .map(|x| x.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    let src = components.iter().rposition(|x| x == "src")?;
+    let mut segments: Vec<&str> = components[src + 1..].iter().map(String::as_str).collect();
+    let stem = segments.pop()?.strip_suffix(".rs")?;
+
+    match (segments.as_slice(), stem) {
+        ([], "lib" | "main") | (["bin"], _) => segments.clear(),
+        (_, "mod") => {}
+        _ => segments.push(stem),
+    }
+
+    parse_module_path(&segments.join("::")).ok()
+}
+
+/// Major version of the spec, by which its api is served like `/v1`
+pub fn spec_version(content: &str) -> Result<usize> {
+    let spec: openapiv3::OpenAPI =
+        serde_yaml::from_str(content).context("Could not deserialize input")?;
+
+    models::extract_major_from_version(&spec.info.version)
+}
+
+fn parse_specs(specs: &[OpenapiWithMeta]) -> Result<Vec<OpenApiWithPath>> {
     let mut openapis: Vec<OpenApiWithPath> = Vec::new();
 
     for spec in specs {
@@ -193,14 +745,225 @@ pub fn generate_api(docs_path: &str, specs: &[OpenapiWithMeta]) -> Result<(Strin
         openapis.push(OpenApiWithPath {
             spec_path: spec.path.to_string(),
             spec: content,
+            served_spec: None,
         });
     }
 
-    let rust_module =
-        to_rust_module(docs_path, &openapis).context("Could not generate rust module")?;
+    Ok(openapis)
+}
+
+pub fn generate_api(
+    docs_path: &str,
+    specs: &[OpenapiWithMeta],
+    options: &ApiOptions,
+) -> Result<(String, String)> {
+    let mut openapis = parse_specs(specs)?;
+
+    for openapi in &mut openapis {
+        openapi.served_spec = served::served_spec(&openapi.spec, options)
+            .with_context(|| format!("Could not prepare served {}", openapi.spec_path))?;
+    }
+
+    let docs_html = StaticStr {
+        path: docs_path.to_string(),
+        content: options.docs_html.clone(),
+    };
+
+    let rust_module = to_rust_module(&docs_html, &openapis, &options.type_map)
+        .context("Could not generate rust module")?;
 
     let serialized_model = serde_yaml::to_string(&rust_module)?;
 
+    let serialized = templates::render_rust_module(
+        convert_module(&rust_module, options),
+        options.templates.as_deref(),
+    )?;
+    let serialized = format::format_code(&serialized, options.included)?;
+
+    Ok((serialized_model, serialized))
+}
+
+/// Docs page for the modules that do not serve it
+fn unserved_docs() -> StaticStr {
+    StaticStr {
+        path: "docs.html".to_string(),
+        content: None,
+    }
+}
+
+/// Generate typed http client calling the api described by specs, with the models derived
+/// and rendered the same way as the ones of the api
+pub fn generate_client(specs: &[OpenapiWithMeta], options: &ApiOptions) -> Result<String> {
+    let openapis = parse_specs(specs)?;
+
+    // Client does not serve docs, so docs path does not matter
+    let rust_module = to_rust_module(&unserved_docs(), &openapis, &options.type_map)
+        .context("Could not generate rust module")?;
+
+    let mut methods = Vec::new();
+
+    for (method_name, method) in &rust_module.api.operations {
+        let Some(path) = client_path(method_name, &rust_module.api.paths) else {
+            bail!("Could not find path of operation {method_name}")
+        };
+        methods.push(
+            convert_client_method(method_name, method, path).with_context(|| {
+                format!("Could not convert operation {method_name} to client method")
+            })?,
+        );
+    }
+
+    let mut encodings: Vec<String> = Vec::new();
+
+    for method in &methods {
+        if let Some(body) = &method.body {
+            if !encodings.contains(&body.encoding) {
+                encodings.push(body.encoding.clone());
+            }
+        }
+    }
+
+    let client_module = templates::ClientModule {
+        api: convert_module(&rust_module, options),
+        methods,
+        encodings,
+    };
+
+    format::format_code(
+        &templates::render_client_module(client_module, options.templates.as_deref())?,
+        false,
+    )
+}
+
+/// Last segment of the path, like `ApiService` of `api::ApiService<S>`
+fn last_segment(path: &syn::Path) -> Option<String> {
+    path.segments.last().map(|x| x.ident.to_string())
+}
+
+/// Collects names of the functions the server type defines in its impl blocks of the api
+/// traits, including nested modules. Other impl blocks, like helpers of the server, are skipped
+fn implemented_fns(
+    items: &[syn::Item],
+    server: &str,
+    traits: &HashSet<String>,
+    names: &mut HashSet<String>,
+) {
+    for item in items {
+        match item {
+            syn::Item::Impl(block) => {
+                let api_trait = block
+                    .trait_
+                    .as_ref()
+                    .and_then(|(_, path, _)| last_segment(path))
+                    .is_some_and(|x| traits.contains(&x));
+
+                let server_type = match block.self_ty.as_ref() {
+                    syn::Type::Path(type_path) => {
+                        last_segment(&type_path.path).is_some_and(|x| x == server)
+                    }
+                    _ => false,
+                };
+
+                if !api_trait || !server_type {
+                    continue;
+                }
+
+                for impl_item in &block.items {
+                    if let syn::ImplItem::Fn(function) = impl_item {
+                        names.insert(function.sig.ident.to_string());
+                    }
+                }
+            }
+            syn::Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    implemented_fns(items, server, traits, names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Generate `todo!()` implementation of the api service for the server type.
+/// When source of existing implementation is given, only methods missing from it are generated
+pub fn generate_stub(
+    specs: &[OpenapiWithMeta],
+    server: &str,
+    implementation: Option<&str>,
+    options: &ApiOptions,
+) -> Result<String> {
+    let openapis = parse_specs(specs)?;
+
+    let rust_module = to_rust_module(&unserved_docs(), &openapis, &options.type_map)
+        .context("Could not generate rust module")?;
+
+    let mut implemented = HashSet::new();
+
+    if let Some(source) = implementation {
+        let file = syn::parse_file(source).context("Could not parse implementation")?;
+
+        let mut traits: HashSet<_> = rust_module
+            .api
+            .operations
+            .values()
+            .map(|op| convert_service(op, options.split_by_tag).title)
+            .collect();
+        traits.insert("Authenticator".to_string());
+
+        implemented_fns(&file.items, server, &traits, &mut implemented);
+    }
+
+    let mut methods = Vec::new();
+
+    for (method_name, method) in &rust_module.api.operations {
+        if implemented.contains(method_name) {
+            continue;
+        }
+        methods.push(convert_method(
+            method_name,
+            method,
+            &HashSet::new(),
+            options,
+        ));
+    }
+
+    let mut authenticators = Vec::new();
+
+    for (def_name, def) in &rust_module.api.definitions {
+        if let models::types::DefinitionData::SecurityScheme(value) = &def.data {
+            let scheme = security::convert_security_scheme(def_name, value);
+            if !implemented.contains(&scheme.authenticate) {
+                authenticators.push(scheme);
+            }
+        }
+    }
+
+    templates::render_stub_module(
+        templates::StubModule {
+            server: server.to_string(),
+            services: collect_services(&rust_module.api.operations, &methods, options.split_by_tag),
+            methods,
+            authenticators,
+            impl_block: implementation.is_none(),
+            split_by_tag: options.split_by_tag,
+        },
+        options.templates.as_deref(),
+    )
+}
+
+/// Path of `Detailed` in the exported macros expanding outside of the generated module
+fn detailed_path(options: &ApiOptions) -> String {
+    match options
+        .module_path
+        .as_deref()
+        .unwrap_or(DEFAULT_MODULE_PATH)
+    {
+        "" => "$crate::Detailed".to_string(),
+        path => format!("$crate::{path}::Detailed"),
+    }
+}
+
+fn convert_module(rust_module: &models::RustModule, options: &ApiOptions) -> templates::RustModule {
     let mut structs = Vec::new();
     let mut enums = Vec::new();
     let mut errors = Vec::new();
@@ -209,37 +972,96 @@ pub fn generate_api(docs_path: &str, specs: &[OpenapiWithMeta]) -> Result<(Strin
     let mut static_strings = Vec::new();
     let mut static_htmls = Vec::new();
     let mut redirects = Vec::new();
+    let mut media_types = Vec::new();
+    let mut validations = Vec::new();
+    let mut security_schemes = Vec::new();
+
+    let validated = validation::validated_definitions(&rust_module.api.definitions);
 
     for (def_name, def) in &rust_module.api.definitions {
-        {
-            use models::types::DefinitionData::*;
-            match &def.data {
-                Struct(value) => structs.push(convert_struct(def_name, value)),
-                Enum(value) => enums.push(convert_enums(def_name, value)),
-                ApiErr(value) => errors.push(convert_error(def_name, value)),
-                DefaultProvider(value) => defaults.push(convert_defaults(def_name, value)),
-                StaticStr(value) => static_includes.push(convert_include(def_name, value)),
-                StaticStringPath(value) => {
-                    static_strings.push(convert_static_string(def_name, value))
+        use models::types::DefinitionData::*;
+        match &def.data {
+            Struct(value) => {
+                if validated.contains(def_name) {
+                    validations.push(validation::struct_validation(def_name, value, &validated))
+                }
+                structs.push(convert_struct(def_name, value))
+            }
+            Enum(value) => {
+                if validated.contains(def_name) {
+                    validations.push(validation::enum_validation(def_name, value, &validated))
                 }
-                StaticHtmlPath(value) => static_htmls.push(convert_static_html(def_name, value)),
-                Redirect(value) => redirects.push(convert_redirect(def_name, value)),
+                enums.push(convert_enums(def_name, value))
+            }
+            ApiErr(value) => errors.push(convert_error(def_name, value)),
+            DefaultProvider(value) => defaults.push(convert_defaults(def_name, value)),
+            StaticStr(value) => static_includes.push(convert_include(def_name, value)),
+            StaticStringPath(value) => static_strings.push(convert_static_string(def_name, value)),
+            StaticHtmlPath(value) => static_htmls.push(convert_static_html(def_name, value)),
+            Redirect(value) => redirects.push(convert_redirect(def_name, value)),
+            MediaTypes(value) => media_types.push(convert_media_types(def_name, value)),
+            SecurityScheme(value) => {
+                security_schemes.push(security::convert_security_scheme(def_name, value))
+            }
+        }
+    }
+
+    let mut body_formats: Vec<String> = Vec::new();
+
+    for media in &media_types {
+        for media_type in &media.media_types {
+            if !body_formats.contains(&media_type.format) {
+                body_formats.push(media_type.format.clone());
             }
         }
     }
 
+    let ndjson = rust_module.api.operations.values().any(|op| {
+        op.param_body
+            .as_ref()
+            .is_some_and(|body| body.any(&|x| matches!(x, InlineType::NdJson(_))))
+    });
+
+    let event_streams = rust_module.api.operations.values().any(|op| {
+        op.response
+            .any(&|x| matches!(x, InlineType::EventStream(_)))
+    });
+
     let mut methods = Vec::new();
 
     for (method_name, method) in &rust_module.api.operations {
-        methods.push(convert_method(method_name, method));
+        methods.push(convert_method(method_name, method, &validated, options));
     }
 
-    let mut paths = Vec::new();
+    let validation = !validations.is_empty()
+        || methods
+            .iter()
+            .any(|method| method.validated || method.validated_stream);
+
+    let mut tag_groups: Vec<templates::TagGroup> = Vec::new();
 
     for path in &rust_module.api.paths {
-        paths.push(convert_method_path(path))
+        let Some(method) = methods.iter().find(|x| x.operation_id == path.operation) else {
+            continue;
+        };
+        let Some(operation) = rust_module.api.operations.get(&path.operation) else {
+            continue;
+        };
+        let path = convert_method_path(path, operation, method, options.split_by_tag);
+        let tag = operation.tags.first().map_or(DEFAULT_TAG, String::as_str);
+
+        match tag_groups.iter_mut().find(|x| x.tag == tag) {
+            Some(group) => group.paths.push(path),
+            None => tag_groups.push(templates::TagGroup {
+                tag: tag.to_string(),
+                implementor: path.implementor.clone(),
+                paths: vec![path],
+            }),
+        }
     }
 
+    let services = collect_services(&rust_module.api.operations, &methods, options.split_by_tag);
+
     let mut static_services = Vec::new();
 
     for service in &rust_module.api.static_services {
@@ -250,21 +1072,36 @@ pub fn generate_api(docs_path: &str, specs: &[OpenapiWithMeta]) -> Result<(Strin
         })
     }
 
-    let rust_module = templates::RustModule {
+    templates::RustModule {
         structs,
         enums,
         defaults,
         errors,
+        media_types,
+        validations,
+        security_schemes,
+        validation,
+        patterns: validation::uses_patterns(
+            &rust_module.api.definitions,
+            &rust_module.api.operations,
+        ),
+        body_formats,
+        ndjson,
+        event_streams,
         methods,
-        paths,
+        tag_groups,
         static_includes,
         static_strings,
         static_htmls,
         static_services,
         redirects,
-    };
-
-    let serialized = templates::render_rust_module(rust_module)?;
-
-    Ok((serialized_model, serialized))
+        mock: options.mock,
+        metrics: options.metrics.as_str().to_string(),
+        tracing: options.tracing,
+        services,
+        split_by_tag: options.split_by_tag,
+        detailed_path: detailed_path(options),
+        included: options.included,
+        derives: options.derives.clone(),
+    }
 }
@@ -0,0 +1,157 @@
+//! Canonical formatting of the rendered code, independent of whitespace of the templates
+
+use anyhow::{bail, Context, Result};
+
+/// Lines shown around the invalid code in the error
+const SNIPPET_CONTEXT: usize = 3;
+
+/// Attribute of every item of the included module, which can not have inner attributes
+const INCLUDED_ALLOW: &str = "#[allow(unused_imports, dead_code, unused_variables)]";
+
+/// Comments outside of the items, kept as is because the printer drops comments.
+/// Templates comment the code inside of the items with tera comments instead
+fn is_top_level_comment(line: &str) -> bool {
+    line.starts_with("//") && !line.starts_with("///") && !line.starts_with("//!")
+}
+
+/// Lines around the given line of the code, numbered and with the line marked
+fn snippet(code: &str, line: usize) -> String {
+    let first = line.saturating_sub(SNIPPET_CONTEXT).max(1);
+    let mut lines = Vec::new();
+
+    for (number, text) in (first..).zip(code.lines().skip(first - 1)) {
+        if number > line + SNIPPET_CONTEXT {
+            break;
+        }
+
+        let marker = if number == line { ">" } else { " " };
+        lines.push(format!("{marker}{number:>5} | {text}"));
+    }
+
+    lines.join("\n")
+}
+
+/// Puts the allow attribute after the doc comment of the printed item.
+/// Lines are counted in the printed text, as spans of the code parsed by the
+/// proc macro point to its call site
+fn allow_unused(item: String) -> String {
+    let mut lines: Vec<_> = item.lines().collect();
+    let docs = lines.iter().take_while(|x| x.starts_with("///")).count();
+    lines.insert(docs, INCLUDED_ALLOW);
+
+    lines.join("\n") + "\n"
+}
+
+/// Pretty prints part of the code between top level comments, separating items by empty line.
+/// `first_line` is the number of the first line of the part in the whole code
+fn format_part(code: &str, part: &str, first_line: usize, included: bool) -> Result<String> {
+    let file = match syn::parse_file(part) {
+        Ok(value) => value,
+        Err(error) => {
+            let line = first_line + error.span().start().line.max(1) - 1;
+            bail!(
+                "Generated code is not valid rust, this is a bug of the generator: {error} at line {line}\n{}",
+                snippet(code, line)
+            )
+        }
+    };
+
+    let mut blocks = Vec::new();
+
+    if !file.attrs.is_empty() {
+        blocks.push(prettyplease::unparse(&syn::File {
+            shebang: None,
+            attrs: file.attrs,
+            items: Vec::new(),
+        }));
+    }
+
+    for item in file.items {
+        // Exported macros are used by the generated code itself
+        let allow = included && !matches!(item, syn::Item::Macro(_));
+
+        let block = prettyplease::unparse(&syn::File {
+            shebang: None,
+            attrs: Vec::new(),
+            items: vec![item],
+        });
+
+        blocks.push(match allow {
+            true => allow_unused(block),
+            false => block,
+        });
+    }
+
+    Ok(blocks.join("\n"))
+}
+
+/// Formats the rendered module, reporting invalid code along with the snippet of it.
+/// Items of the `included` module are allowed to be unused, as it gets only the parts
+/// of the api used by its crate
+pub fn format_code(code: &str, included: bool) -> Result<String> {
+    let mut blocks = Vec::new();
+    let mut comments = String::new();
+    let mut part = String::new();
+    let mut part_line = 1;
+
+    for (index, line) in code.lines().enumerate() {
+        if !is_top_level_comment(line) {
+            part.push_str(line);
+            part.push('\n');
+            continue;
+        }
+
+        if !part.is_empty() {
+            blocks.push(std::mem::take(&mut comments));
+            blocks.push(format_part(code, &part, part_line, included)?);
+            part.clear();
+        }
+
+        part_line = index + 2;
+        comments.push_str(line);
+        comments.push('\n');
+    }
+
+    blocks.push(comments);
+    blocks.push(format_part(code, &part, part_line, included)?);
+    blocks.retain(|x| !x.is_empty());
+
+    Ok(blocks.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_code_comments() {
+        let code = "// Models\n// ---\nstruct A { x:u8 }\nfn f() {\n    // dropped\n    g()\n}\n";
+
+        assert_eq!(
+            format_code(code, false).unwrap(),
+            "// Models\n// ---\n\nstruct A {\n    x: u8,\n}\n\nfn f() {\n    g()\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_code_included() {
+        let code =
+            "/// Doc\nstruct A;\nuse std::{\n    fmt::Debug,\n};\nmacro_rules! m { () => {} }\n";
+
+        assert_eq!(
+            format_code(code, true).unwrap(),
+            format!("/// Doc\n{INCLUDED_ALLOW}\nstruct A;\n\n{INCLUDED_ALLOW}\nuse std::fmt::Debug;\n\nmacro_rules! m {{\n    () => {{}};\n}}\n")
+        );
+    }
+
+    #[test]
+    fn test_format_code_invalid() {
+        let error = format_code("fn f() {\n    let = 1;\n}\n", false).unwrap_err();
+
+        assert!(error.to_string().contains("at line 2"), "{error}");
+        assert!(
+            error.to_string().contains(">    2 |     let = 1;"),
+            "{error}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use convert_case::Case;
 use indexmap::{IndexMap, IndexSet};
-use openapiv3::{OpenAPI, Parameter, ReferenceOr};
+use openapiv3::{OpenAPI, Parameter, ReferenceOr, SecurityRequirement};
 use serde::Serialize;
 pub mod types;
 use anyhow::{bail, Context, Result};
@@ -8,8 +8,9 @@ use anyhow::{bail, Context, Result};
 use crate::openapictx::OpenApiCtx;
 
 use self::types::{
-    to_rust_identifier, Definition, DefinitionMaker, HttpMethod, Inlining, MaybeInlining,
-    OperationPath, RustOperation, StaticHtmlPath, StaticRedirect, StaticStr, StaticStringPath,
+    body_constraints, to_rust_identifier, Constraints, Definition, DefinitionMaker, HttpMethod,
+    Inlining, MaybeInlining, OperationPath, RSecurityScheme, RustOperation, StaticHtmlPath,
+    StaticRedirect, StaticStr, StaticStringPath,
 };
 
 /// Reference to ApiErr definition
@@ -57,6 +58,7 @@ fn to_operation_map(
     result
 }
 
+#[allow(clippy::too_many_arguments)]
 fn to_rust_operation(
     ctx: &OpenApiCtx,
     defmaker: &mut DefinitionMaker,
@@ -64,6 +66,8 @@ fn to_rust_operation(
     method: HttpMethod,
     operation: &openapiv3::Operation,
     global_params: &[ReferenceOr<Parameter>],
+    global_security: &Option<Vec<SecurityRequirement>>,
+    security_schemes: &IndexMap<String, String>,
     version: usize,
 ) -> Result<Vec<OperationPath>> {
     // Get operation name
@@ -107,6 +111,47 @@ fn to_rust_operation(
         .inline(format!("{name_upper}Body"), version, ctx, defmaker)
         .context("Could not inline Body")?;
 
+    // Media type clients should send the body with, json is preferred when there is a choice
+    let body_media_type = match &operation.request_body {
+        Some(body) => {
+            let content = &ctx.deref(body)?.content;
+            match content.contains_key("application/json") {
+                true => Some("application/json".to_string()),
+                false => content.keys().next().cloned(),
+            }
+        }
+        None => None,
+    };
+
+    let body_constraints = match &operation.request_body {
+        Some(body) => {
+            body_constraints(ctx.deref(body)?, ctx).context("Could not get constraints of body")?
+        }
+        None => Constraints::default(),
+    };
+
+    let mut security = Vec::new();
+
+    let requirements = operation.security.as_ref().or(global_security.as_ref());
+
+    for requirement in requirements.into_iter().flatten() {
+        let mut schemes = IndexMap::new();
+
+        for (scheme, scopes) in requirement {
+            let Some(definition) = security_schemes.get(scheme) else {
+                bail!("Unknown security scheme {scheme}")
+            };
+            schemes.insert(definition.clone(), scopes.clone());
+        }
+
+        security.push(schemes);
+    }
+
+    // Operation allowing only anonymous calls does not need authentication
+    if security.iter().all(|schemes| schemes.is_empty()) {
+        security.clear();
+    }
+
     let response = operation
         .responses
         .inline(name_upper, version, ctx, defmaker)
@@ -119,6 +164,10 @@ fn to_rust_operation(
         param_path: path_params_inline,
         param_query: query_params_inline,
         param_body,
+        body_media_type,
+        body_constraints,
+        security,
+        tags: operation.tags.clone(),
 
         // Response
         // -----------------------------
@@ -150,8 +199,9 @@ fn to_rust_operation(
 pub fn to_openapi_site(
     version: usize,
     path: String,
-    path_html: String,
+    docs_html: StaticStr,
     path_openapi: String,
+    served_openapi: Option<String>,
     defmaker: &mut DefinitionMaker,
 ) -> Result<Vec<StaticService>> {
     let mut services = Vec::new();
@@ -160,7 +210,10 @@ pub fn to_openapi_site(
         "DOCS_OPENAPI".to_string(),
         version,
         Definition {
-            data: types::DefinitionData::StaticStr(StaticStr { path: path_openapi }),
+            data: types::DefinitionData::StaticStr(StaticStr {
+                path: path_openapi,
+                content: served_openapi,
+            }),
         },
     )?;
 
@@ -168,7 +221,7 @@ pub fn to_openapi_site(
         "DOCS_HTML".to_string(),
         version,
         Definition {
-            data: types::DefinitionData::StaticStr(StaticStr { path: path_html }),
+            data: types::DefinitionData::StaticStr(docs_html),
         },
     )?;
 
@@ -208,6 +261,8 @@ pub fn to_openapi_site(
 pub struct OpenApiWithPath {
     pub spec_path: String,
     pub spec: OpenAPI,
+    /// Content of the spec served instead of the file at `spec_path`
+    pub served_spec: Option<String>,
 }
 
 pub fn extract_major_from_version(version: &str) -> Result<usize> {
@@ -248,7 +303,29 @@ fn add_redirect(
     })
 }
 
-pub fn to_rust_module(doc_path: &str, specs: &[OpenApiWithPath]) -> Result<RustModule> {
+/// Names of the security schemes required by the operations of the spec
+fn required_schemes(spec: &OpenAPI, ctx: &OpenApiCtx) -> Result<IndexSet<String>> {
+    let mut schemes = IndexSet::new();
+
+    for (_, path_item) in spec.paths.iter() {
+        for (_, operation) in to_operation_map(ctx.deref(path_item)?) {
+            let requirements = operation.security.as_ref().or(spec.security.as_ref());
+
+            for requirement in requirements.into_iter().flatten() {
+                schemes.extend(requirement.keys().cloned());
+            }
+        }
+    }
+
+    Ok(schemes)
+}
+
+/// Converts specs into rust module serving them along with the given docs page
+pub fn to_rust_module(
+    docs_html: &StaticStr,
+    specs: &[OpenApiWithPath],
+    type_map: &IndexMap<String, String>,
+) -> Result<RustModule> {
     let mut operations = IndexMap::new();
     let mut paths = Vec::new();
     let mut static_services = Vec::new();
@@ -257,9 +334,14 @@ pub fn to_rust_module(doc_path: &str, specs: &[OpenApiWithPath]) -> Result<RustM
 
     let mut seen_version = IndexSet::new();
 
-    let mut defmaker = DefinitionMaker::new(&mut definitions, &mut operations);
+    let mut defmaker = DefinitionMaker::new(&mut definitions, &mut operations, type_map.clone());
 
-    for OpenApiWithPath { spec, spec_path } in specs {
+    for OpenApiWithPath {
+        spec,
+        spec_path,
+        served_spec,
+    } in specs
+    {
         let ctx = OpenApiCtx::new(&spec.components);
 
         let version =
@@ -273,8 +355,9 @@ pub fn to_rust_module(doc_path: &str, specs: &[OpenApiWithPath]) -> Result<RustM
             static_services.extend(to_openapi_site(
                 version,
                 "".to_string(),
-                doc_path.to_string(),
+                docs_html.clone(),
                 spec_path.clone(),
+                served_spec.clone(),
                 &mut defmaker,
             )?);
         }
@@ -298,11 +381,39 @@ pub fn to_rust_module(doc_path: &str, specs: &[OpenApiWithPath]) -> Result<RustM
         static_services.extend(to_openapi_site(
             version,
             format!("/v{version}"),
-            doc_path.to_string(),
+            docs_html.clone(),
             spec_path.clone(),
+            served_spec.clone(),
             &mut defmaker,
         )?);
 
+        let mut security_schemes = IndexMap::new();
+
+        // Schemes no operation requires are not converted, so that the spec may declare
+        // ones that are not supported
+        let required = required_schemes(spec, &ctx)?;
+
+        if let Some(components) = &spec.components {
+            for (name, scheme) in &components.security_schemes {
+                if !required.contains(name) {
+                    continue;
+                }
+
+                let scheme = RSecurityScheme::from_scheme(name, ctx.deref(scheme)?)
+                    .with_context(|| format!("Could not convert security scheme {name}"))?;
+
+                let definition = defmaker.push(
+                    to_rust_identifier(name, Case::UpperCamel),
+                    version,
+                    types::Definition {
+                        data: types::DefinitionData::SecurityScheme(scheme),
+                    },
+                )?;
+
+                security_schemes.insert(name.clone(), definition);
+            }
+        }
+
         for (path, path_item) in spec.paths.iter() {
             let path_item = ctx.deref(path_item)?;
             let global_params: &Vec<ReferenceOr<Parameter>> = &path_item.parameters;
@@ -314,6 +425,8 @@ pub fn to_rust_module(doc_path: &str, specs: &[OpenApiWithPath]) -> Result<RustM
                     method,
                     operation,
                     global_params,
+                    &spec.security,
+                    &security_schemes,
                     version,
                 )
                 .with_context(|| {
@@ -9,8 +9,9 @@ use std::{
 use convert_case::{Case, Casing};
 use indexmap::IndexMap;
 use openapiv3::{
-    MediaType, ObjectType, ParameterData, ReferenceOr, RequestBody, Response, Responses, Schema,
-    SchemaData, SchemaKind, StatusCode, Type,
+    APIKeyLocation, MediaType, ObjectType, ParameterData, ReferenceOr, RequestBody, Response,
+    Responses, Schema, SchemaData, SchemaKind, SecurityScheme, StatusCode, StringFormat,
+    StringType, Type, VariantOrUnknownOrEmpty,
 };
 use serde::{Serialize, Serializer};
 
@@ -137,6 +138,170 @@ impl Inlining for IndexMap<String, MediaType> {
     }
 }
 
+/// Request body that can be sent in any of several serialization formats.
+/// All media types must share the same schema, the extractor picks the decoder
+/// based on `Content-Type` header.
+fn negotiated_inline(
+    content: &IndexMap<String, MediaType>,
+    name: String,
+    version: usize,
+    ctx: &OpenApiCtx<'_>,
+    defmaker: &mut DefinitionMaker,
+) -> Result<InlineType> {
+    let mut media_types = Vec::new();
+    let mut body_schema: Option<&Schema> = None;
+
+    for (media_type, media) in content {
+        let Some(format) = BodyFormat::from_media_type(media_type) else {
+            bail!("Media type {media_type} can not be combined with other media types")
+        };
+
+        let Some(schema) = &media.schema else {
+            bail!("Content {media_type} must have schema specified")
+        };
+        let schema = ctx.deref(schema)?;
+
+        match body_schema {
+            Some(existing) if existing != schema => {
+                bail!("All media types of request body must have the same schema")
+            }
+            _ => body_schema = Some(schema),
+        }
+
+        media_types.push(RMediaType {
+            media_type: media_type.clone(),
+            format,
+        });
+    }
+
+    let Some(schema) = body_schema else {
+        bail!("Request body must have at least one media type")
+    };
+
+    let inner = schema.inline(name, version, ctx, defmaker)?;
+
+    let subtypes: Vec<&str> = media_types
+        .iter()
+        .map(|x| x.media_type.rsplit('/').next().unwrap_or_default())
+        .collect();
+
+    let media_name = to_rust_identifier(&format!("{} media", subtypes.join(" ")), Case::UpperCamel);
+
+    let definition = defmaker.push(
+        media_name,
+        version,
+        Definition {
+            data: DefinitionData::MediaTypes(RMediaTypes { media_types }),
+        },
+    )?;
+
+    Ok(InlineType::Negotiated(Box::new(inner), definition))
+}
+
+/// Media types of newline delimited json bodies
+const NDJSON_MEDIA_TYPES: &[&str] = &[
+    "application/x-ndjson",
+    "application/ndjson",
+    "application/jsonl",
+    "application/x-jsonlines",
+];
+
+/// Get string schema if it describes raw binary data
+fn binary_string(schema: &Schema) -> Option<&StringType> {
+    let SchemaKind::Type(Type::String(value)) = &schema.schema_kind else {
+        return None;
+    };
+
+    match value.format {
+        VariantOrUnknownOrEmpty::Item(StringFormat::Binary) => Some(value),
+        _ => None,
+    }
+}
+
+/// Request body that is not buffered into a struct
+/// Returns None if body should be deserialized with one of [`BodyFormat`]s
+fn raw_body_inline(
+    media_type: &str,
+    media: &MediaType,
+    name: String,
+    version: usize,
+    ctx: &OpenApiCtx<'_>,
+    defmaker: &mut DefinitionMaker,
+) -> Result<Option<InlineType>> {
+    let schema = match &media.schema {
+        Some(value) => Some(ctx.deref(value)?),
+        None => None,
+    };
+
+    if NDJSON_MEDIA_TYPES.contains(&media_type) {
+        let Some(schema) = schema else {
+            bail!("Content {media_type} must have schema specified")
+        };
+
+        // Array schema describes the whole stream, otherwise schema describes single item
+        let item = match &schema.schema_kind {
+            SchemaKind::Type(Type::Array(value)) => match &value.items {
+                Some(items) => {
+                    ctx.deref_boxed(items)?
+                        .inline(format!("{name}Item"), version, ctx, defmaker)?
+                }
+                None => InlineType::Any,
+            },
+            _ => schema.inline(name, version, ctx, defmaker)?,
+        };
+
+        return Ok(Some(InlineType::NdJson(Box::new(item))));
+    }
+
+    if BodyFormat::from_media_type(media_type).is_some() {
+        return Ok(None);
+    }
+
+    let binary = match schema {
+        Some(schema) => binary_string(schema),
+        None => None,
+    };
+
+    if media_type == "text/plain" && binary.is_none() {
+        return match schema.map(|x| &x.schema_kind) {
+            None | Some(SchemaKind::Type(Type::String(_))) => Ok(Some(InlineType::String)),
+            Some(_) => bail!("Content {media_type} must have string schema"),
+        };
+    }
+
+    if schema.is_some() && binary.is_none() {
+        bail!("Content {media_type} must have string schema with 'format: binary'")
+    }
+
+    // Bodies with known maximum length are small enough to be buffered
+    match binary.and_then(|x| x.max_length) {
+        Some(limit) => Ok(Some(InlineType::Bytes(limit))),
+        None => Ok(Some(InlineType::Payload)),
+    }
+}
+
+/// Constraints of the request body, which all of its media types share.
+/// Newline delimited json with array schema is checked item by item
+pub fn body_constraints(body: &RequestBody, ctx: &OpenApiCtx<'_>) -> Result<Constraints> {
+    let Some((media_type, media)) = body.content.iter().next() else {
+        return Ok(Constraints::default());
+    };
+
+    let Some(schema) = &media.schema else {
+        return Ok(Constraints::default());
+    };
+    let schema = ctx.deref(schema)?;
+
+    let constraints = Constraints::from_schema(schema, ctx)?;
+
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Array(_)) if NDJSON_MEDIA_TYPES.contains(&media_type.as_str()) => {
+            Ok(constraints.items.map(|x| *x).unwrap_or_default())
+        }
+        _ => Ok(constraints),
+    }
+}
+
 impl Inlining for RequestBody {
     fn inline(
         &self,
@@ -145,7 +310,22 @@ impl Inlining for RequestBody {
         ctx: &OpenApiCtx<'_>,
         defmaker: &mut DefinitionMaker,
     ) -> Result<InlineType> {
-        let inner = self.content.inline(name, version, ctx, defmaker)?;
+        let raw = match self.content.iter().next() {
+            Some((media_type, media)) if self.content.len() == 1 => {
+                raw_body_inline(media_type, media, name.clone(), version, ctx, defmaker)?
+            }
+            _ => None,
+        };
+
+        let json_only = self.content.len() == 1 && self.content.contains_key("application/json");
+
+        let inner = if let Some(raw) = raw {
+            raw
+        } else if json_only {
+            self.content.inline(name, version, ctx, defmaker)?
+        } else {
+            negotiated_inline(&self.content, name, version, ctx, defmaker)?
+        };
         if self.required {
             Ok(inner)
         } else {
@@ -274,12 +454,14 @@ impl Inlining for IndexMap<&StatusCode, &ReferenceOr<Response>> {
     }
 }
 
-fn is_success(code: &StatusCode) -> bool {
+/// Status of successful response, `Range` holds only the first digit of `2XX`
+pub fn is_success(code: &StatusCode) -> bool {
     match code {
-        StatusCode::Code(value) => (&200..&300).contains(&value),
-        StatusCode::Range(value) => (&200..&300).contains(&value),
+        StatusCode::Code(value) => (200..300).contains(value),
+        StatusCode::Range(value) => *value == 2,
     }
 }
+
 /// Get success response code
 /// If there is more that one success response, Returns an error
 fn get_success_response(
@@ -318,6 +500,35 @@ impl Inlining for Response {
         ctx: &OpenApiCtx<'_>,
         defmaker: &mut DefinitionMaker,
     ) -> Result<InlineType> {
+        // Server-sent events, schema describes payload of a single event
+        if let Some(media) = self.content.get("text/event-stream") {
+            if self.content.len() > 1 {
+                bail!("text/event-stream can not be combined with other media types")
+            }
+            let Some(schema) = &media.schema else {
+                bail!("Content text/event-stream must have schema specified")
+            };
+            let item = ctx
+                .deref(schema)?
+                .inline(format!("{name}Event"), version, ctx, defmaker)?;
+            return Ok(InlineType::EventStream(Box::new(item)));
+        }
+
+        // Plain text is responded as string, which actix sends as `text/plain`
+        if let Some(media) = self.content.get("text/plain") {
+            if self.content.len() > 1 {
+                bail!("text/plain can not be combined with other media types")
+            }
+            let schema = match &media.schema {
+                Some(value) => Some(ctx.deref(value)?),
+                None => None,
+            };
+            return match schema.map(|x| &x.schema_kind) {
+                None | Some(SchemaKind::Type(Type::String(_))) => Ok(InlineType::String),
+                Some(_) => bail!("Content text/plain must have string schema"),
+            };
+        }
+
         self.content.inline(name, version, ctx, defmaker)
     }
 }
@@ -333,8 +544,12 @@ impl Inlining for Responses {
         // Render success response
         let (success_response_code, success_response) = get_success_response(&self.responses)?;
 
-        if success_response_code != &StatusCode::Code(200) {
-            bail!("Only success code '200' supported")
+        // Handler responds with 200, which is also what `2XX` allows
+        if !matches!(
+            success_response_code,
+            StatusCode::Code(200) | StatusCode::Range(2)
+        ) {
+            bail!("Only success code '200' or '2XX' supported")
         }
 
         let success_inline = success_response.inline(name.clone(), version, ctx, defmaker)?;
@@ -406,12 +621,17 @@ where
         parameter_schema.schema_data.nullable,
     )?;
 
+    let constraints = Constraints::from_schema(parameter_schema, ctx)
+        .with_context(|| format!("Could not get constraints of {}", &param_data.name))?
+        .of_type(&inline);
+
     Ok(RStructProp {
         name: to_rust_identifier(&param.data().name, Case::Snake),
         rename: param.data().name.clone(),
         default,
         type_: inline,
         doc: param_data.description.clone(),
+        constraints,
     })
 }
 
@@ -547,6 +767,27 @@ fn remove_options<T>(arr: &Vec<Option<T>>) -> Result<Vec<&T>> {
     Ok(without_options)
 }
 
+/// Format of the string, integer or number schema as written in the spec
+fn schema_format(schema_type: &Type) -> Option<String> {
+    fn as_str<T: Serialize>(format: &VariantOrUnknownOrEmpty<T>) -> Option<String> {
+        match format {
+            VariantOrUnknownOrEmpty::Item(value) => match serde_json::to_value(value) {
+                Ok(serde_json::Value::String(value)) => Some(value),
+                _ => None,
+            },
+            VariantOrUnknownOrEmpty::Unknown(value) => Some(value.clone()),
+            VariantOrUnknownOrEmpty::Empty => None,
+        }
+    }
+
+    match schema_type {
+        Type::String(value) => as_str(&value.format),
+        Type::Integer(value) => as_str(&value.format),
+        Type::Number(value) => as_str(&value.format),
+        _ => None,
+    }
+}
+
 fn schema_type_to_inline_type(
     name: String,
     version: usize,
@@ -555,8 +796,11 @@ fn schema_type_to_inline_type(
     schema_type: &Type,
     schema_data: &SchemaData,
 ) -> Result<InlineType> {
-    let mut type_ = match schema_type {
-        Type::String(value) => {
+    let mapped = schema_format(schema_type).and_then(|x| defmaker.type_map.get(&x).cloned());
+
+    let mut type_ = match (mapped, schema_type) {
+        (Some(path), _) => InlineType::Mapped(path),
+        (None, Type::String(value)) => {
             if value.enumeration.is_empty() {
                 InlineType::String
             } else {
@@ -566,14 +810,14 @@ fn schema_type_to_inline_type(
                 enum_inline(name, version, defmaker, variants, &schema_data.description)?
             }
         }
-        Type::Number(_) => InlineType::Float,
-        Type::Integer(_) => InlineType::Integer,
-        Type::Boolean {} => InlineType::Boolean,
-        Type::Object(val) => {
+        (None, Type::Number(_)) => InlineType::Float,
+        (None, Type::Integer(_)) => InlineType::Integer,
+        (None, Type::Boolean {}) => InlineType::Boolean,
+        (None, Type::Object(val)) => {
             let name = get_schema_name(name, &schema_data.title);
             inline_obj(val, name, version, ctx, defmaker, &schema_data.description)?
         }
-        Type::Array(val) => {
+        (None, Type::Array(val)) => {
             let new_inline = match &val.items {
                 Some(value) => {
                     let deref = ctx.deref_boxed(value)?;
@@ -908,12 +1152,17 @@ fn inline_obj(
         )
         .with_context(|| format!("Could not validate required and nullable for {prop_name}"))?;
 
+        let constraints = Constraints::from_schema(prop_schema, ctx)
+            .with_context(|| format!("Could not get constraints of {prop_name}"))?
+            .of_type(&type_);
+
         properties.push(RStructProp {
             name: to_rust_identifier(prop_name, Case::Snake),
             rename: prop_name.clone(),
             default,
             type_,
             doc: prop_schema.schema_data.description.clone(),
+            constraints,
         })
     }
 
@@ -960,6 +1209,18 @@ pub struct RustOperation {
     pub param_path: Option<InlineType>,  // web::Path
     pub param_query: Option<InlineType>, // web::Query
     pub param_body: Option<InlineType>,  // web::Json
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_media_type: Option<String>,
+    /// Constraints of the request body schema, of a single item for newline delimited json
+    #[serde(skip_serializing_if = "Constraints::is_empty")]
+    pub body_constraints: Constraints,
+    /// Alternative sets of security schemes with the scopes they require,
+    /// empty set allows anonymous calls
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<IndexMap<String, Vec<String>>>,
+    /// Tags grouping the operation in the spec
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 
     // Response
     // -----------------------------
@@ -971,16 +1232,20 @@ pub struct RustOperation {
 pub struct DefinitionMaker<'a, 'b> {
     pub dedup_store: &'a mut IndexMap<String, Definition>,
     pub operations: &'b mut IndexMap<String, RustOperation>,
+    /// Rust types of the schemas by their `format`, used instead of the generated ones
+    pub type_map: IndexMap<String, String>,
 }
 
 impl<'a, 'b> DefinitionMaker<'a, 'b> {
     pub fn new(
         store: &'a mut IndexMap<String, Definition>,
         operations: &'b mut IndexMap<String, RustOperation>,
+        type_map: IndexMap<String, String>,
     ) -> Self {
         DefinitionMaker {
             dedup_store: store,
             operations,
+            type_map,
         }
     }
 
@@ -1049,6 +1314,35 @@ pub enum InlineType {
     Reference(String),
     Result(Box<InlineType>, Box<InlineType>),
     Detailed(Box<InlineType>),
+    Negotiated(Box<InlineType>, String), // Negotiated<InlineType, MediaTypes>
+    NdJson(Box<InlineType>),             // NdJson<InlineType>
+    Payload,                             // web::Payload
+    Bytes(usize),                        // web::Bytes of at most the given length
+    EventStream(Box<InlineType>),        // EventStream<InlineType>
+    Mapped(String),                      // Type given by the type map
+}
+
+impl InlineType {
+    /// Check if this type or any of the nested types satisfy the predicate
+    pub fn any(&self, predicate: &impl Fn(&InlineType) -> bool) -> bool {
+        if predicate(self) {
+            return true;
+        }
+
+        match self {
+            InlineType::Array(item)
+            | InlineType::Json(item)
+            | InlineType::Path(item)
+            | InlineType::Query(item)
+            | InlineType::Option(item)
+            | InlineType::Detailed(item)
+            | InlineType::Negotiated(item, _)
+            | InlineType::NdJson(item)
+            | InlineType::EventStream(item) => item.any(predicate),
+            InlineType::Result(ok, err) => ok.any(predicate) || err.any(predicate),
+            _ => false,
+        }
+    }
 }
 
 impl Display for InlineType {
@@ -1064,9 +1358,14 @@ impl Display for InlineType {
             InlineType::Path(item) => write!(f, "web::Path<{item}>"),
             InlineType::Query(item) => write!(f, "web::Query<{item}>"),
             InlineType::Option(item) => write!(f, "Option<{item}>"),
-            InlineType::Reference(item) => Display::fmt(&item, f),
+            InlineType::Reference(item) | InlineType::Mapped(item) => Display::fmt(&item, f),
             InlineType::Result(ok, err) => write!(f, "Result<{ok}, {err}>"),
             InlineType::Detailed(item) => write!(f, "Detailed<{item}>"),
+            InlineType::Negotiated(item, media) => write!(f, "Negotiated<{item}, {media}>"),
+            InlineType::NdJson(item) => write!(f, "NdJson<{item}>"),
+            InlineType::Payload => write!(f, "web::Payload"),
+            InlineType::Bytes(_) => write!(f, "web::Bytes"),
+            InlineType::EventStream(item) => write!(f, "EventStream<{item}>"),
         }
     }
 }
@@ -1080,6 +1379,148 @@ impl Serialize for InlineType {
     }
 }
 
+/// Inclusive or exclusive limit of the number
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Bound {
+    /// Rust literal of the limit
+    pub value: String,
+    pub exclusive: bool,
+}
+
+/// String formats that can be checked at runtime
+const CHECKED_FORMATS: &[&str] = &["date", "date-time", "email", "uuid", "ipv4", "ipv6", "uri"];
+
+/// Constraints on the value declared in the schema, checked at runtime
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct Constraints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<Bound>,
+    /// Rust literal of the divisor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unique_items: bool,
+    /// Constraints on every item of the array
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Constraints>>,
+}
+
+impl Constraints {
+    pub fn from_schema(schema: &Schema, ctx: &OpenApiCtx<'_>) -> Result<Self> {
+        let SchemaKind::Type(schema_type) = &schema.schema_kind else {
+            return Ok(Constraints::default());
+        };
+
+        let constraints = match schema_type {
+            Type::String(value) => {
+                let format = match &value.format {
+                    VariantOrUnknownOrEmpty::Item(StringFormat::Date) => Some("date"),
+                    VariantOrUnknownOrEmpty::Item(StringFormat::DateTime) => Some("date-time"),
+                    VariantOrUnknownOrEmpty::Unknown(format) => Some(format.as_str()),
+                    _ => None,
+                };
+
+                // Checked at runtime with the `regex` crate, so the spec must not rely
+                // on ECMA-262 features it lacks, like lookaround and backreferences
+                if let Some(pattern) = &value.pattern {
+                    if let Err(error) = regex::Regex::new(pattern) {
+                        bail!("Invalid pattern {pattern:?}: {error}");
+                    }
+                }
+
+                Constraints {
+                    min_length: value.min_length,
+                    max_length: value.max_length,
+                    pattern: value.pattern.clone(),
+                    format: format
+                        .filter(|x| CHECKED_FORMATS.contains(x))
+                        .map(|x| x.to_string()),
+                    ..Default::default()
+                }
+            }
+            Type::Integer(value) => Constraints {
+                minimum: value.minimum.map(|x| Bound {
+                    value: x.to_string(),
+                    exclusive: value.exclusive_minimum,
+                }),
+                maximum: value.maximum.map(|x| Bound {
+                    value: x.to_string(),
+                    exclusive: value.exclusive_maximum,
+                }),
+                multiple_of: value.multiple_of.map(|x| x.to_string()),
+                ..Default::default()
+            },
+            Type::Number(value) => Constraints {
+                minimum: value.minimum.map(|x| Bound {
+                    value: format!("{x:?}"),
+                    exclusive: value.exclusive_minimum,
+                }),
+                maximum: value.maximum.map(|x| Bound {
+                    value: format!("{x:?}"),
+                    exclusive: value.exclusive_maximum,
+                }),
+                multiple_of: value.multiple_of.map(|x| format!("{x:?}")),
+                ..Default::default()
+            },
+            Type::Array(value) => {
+                let items = match &value.items {
+                    Some(items) => {
+                        let items = ctx.deref_boxed(items)?;
+                        Some(Box::new(Constraints::from_schema(items, ctx)?))
+                    }
+                    None => None,
+                };
+
+                Constraints {
+                    min_items: value.min_items,
+                    max_items: value.max_items,
+                    unique_items: value.unique_items,
+                    items: items.filter(|x| !x.is_empty()),
+                    ..Default::default()
+                }
+            }
+            Type::Object(_) | Type::Boolean {} => Constraints::default(),
+        };
+
+        Ok(constraints)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Constraints::default()
+    }
+
+    /// Constraints left to check on the value of the type, as types given by the type map
+    /// are checked by their own deserialization
+    pub fn of_type(mut self, type_: &InlineType) -> Self {
+        match type_ {
+            InlineType::Mapped(_) => Constraints::default(),
+            InlineType::Option(inner) => self.of_type(inner),
+            InlineType::Array(item) => {
+                self.items = self
+                    .items
+                    .map(|x| Box::new(x.of_type(item)))
+                    .filter(|x| !x.is_empty());
+                self
+            }
+            _ => self,
+        }
+    }
+}
+
 /// Something that can serialize into rust struct property
 #[derive(Debug, Serialize, PartialEq, Eq)]
 pub struct RStructProp {
@@ -1088,6 +1529,8 @@ pub struct RStructProp {
     pub default: Option<InlineType>,
     pub type_: InlineType,
     pub doc: Option<String>,
+    #[serde(skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,
 }
 
 /// Something that can serialize into rust struct
@@ -1125,15 +1568,59 @@ pub struct REnum {
     pub discriminator: Option<String>,
 }
 
+/// Serialization format of the request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum BodyFormat {
+    Json,
+    Cbor,
+    MsgPack,
+}
+
+impl BodyFormat {
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/json" => Some(BodyFormat::Json),
+            "application/cbor" => Some(BodyFormat::Cbor),
+            "application/msgpack" | "application/x-msgpack" => Some(BodyFormat::MsgPack),
+            _ => None,
+        }
+    }
+}
+
+impl Display for BodyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyFormat::Json => write!(f, "Json"),
+            BodyFormat::Cbor => write!(f, "Cbor"),
+            BodyFormat::MsgPack => write!(f, "MsgPack"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct RMediaType {
+    pub media_type: String,
+    pub format: BodyFormat,
+}
+
+/// Set of media types accepted by the request body
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct RMediaTypes {
+    pub media_types: Vec<RMediaType>,
+}
+
 #[derive(Debug, Serialize, PartialEq, Eq)]
 pub struct DefaultProvider {
     pub vtype: InlineType,
     pub value: String,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub struct StaticStr {
     pub path: String,
+    /// Content embedded instead of including the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
 }
 
 /// Serves static string on given path
@@ -1154,6 +1641,80 @@ pub struct StaticRedirect {
     pub target: String,
 }
 
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+    Cookie,
+}
+
+/// How the credentials of the security scheme are sent
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub enum CredentialsKind {
+    /// Token in `Authorization: Bearer` header, also used by OAuth2 and OpenID Connect
+    Bearer,
+    /// Username and password in `Authorization: Basic` header
+    Basic,
+    ApiKey {
+        location: ApiKeyLocation,
+        name: String,
+    },
+}
+
+/// Security scheme the credentials are extracted for
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct RSecurityScheme {
+    /// Name of the scheme in the spec, schemes with same credentials are still distinct
+    pub name: String,
+    pub doc: Option<String>,
+    pub kind: CredentialsKind,
+}
+
+impl RSecurityScheme {
+    pub fn from_scheme(name: &str, scheme: &SecurityScheme) -> Result<Self> {
+        let (kind, doc) = match scheme {
+            SecurityScheme::APIKey {
+                location,
+                name,
+                description,
+            } => {
+                let location = match location {
+                    APIKeyLocation::Header => ApiKeyLocation::Header,
+                    APIKeyLocation::Query => ApiKeyLocation::Query,
+                    APIKeyLocation::Cookie => ApiKeyLocation::Cookie,
+                };
+                let kind = CredentialsKind::ApiKey {
+                    location,
+                    name: name.clone(),
+                };
+                (kind, description)
+            }
+            SecurityScheme::HTTP {
+                scheme,
+                description,
+                ..
+            } => {
+                let kind = match scheme.to_lowercase().as_str() {
+                    "bearer" => CredentialsKind::Bearer,
+                    "basic" => CredentialsKind::Basic,
+                    other => bail!("Http authentication scheme {other} is not supported"),
+                };
+                (kind, description)
+            }
+            SecurityScheme::OAuth2 { description, .. }
+            | SecurityScheme::OpenIDConnect { description, .. } => {
+                (CredentialsKind::Bearer, description)
+            }
+        };
+
+        Ok(RSecurityScheme {
+            name: name.to_string(),
+            doc: doc.clone(),
+            kind,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Eq)]
 pub enum DefinitionData {
     Struct(RStruct),
@@ -1164,6 +1725,8 @@ pub enum DefinitionData {
     StaticStringPath(StaticStringPath),
     StaticHtmlPath(StaticHtmlPath),
     Redirect(StaticRedirect),
+    MediaTypes(RMediaTypes),
+    SecurityScheme(RSecurityScheme),
 }
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
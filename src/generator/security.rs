@@ -0,0 +1,197 @@
+//! Authentication of the requests with security schemes declared in the spec
+
+use convert_case::{Case, Casing};
+use indexmap::IndexSet;
+
+use super::{
+    models::types::{
+        to_rust_identifier, ApiKeyLocation, CredentialsKind, RSecurityScheme, RustOperation,
+    },
+    templates::{self, quote_str},
+};
+
+/// Method of `Authenticator` checking credentials of the scheme
+fn authenticate_fn(scheme: &str) -> String {
+    format!("authenticate_{}", scheme.to_case(Case::Snake))
+}
+
+/// Variable holding credentials of the scheme extracted from the request
+fn credentials_var(scheme: &str) -> String {
+    format!("{}_credentials", scheme.to_case(Case::Snake))
+}
+
+pub fn convert_security_scheme(
+    name: &str,
+    scheme: &RSecurityScheme,
+) -> templates::RustSecurityScheme {
+    let (kind, location, key_name) = match &scheme.kind {
+        CredentialsKind::Bearer => ("bearer", None, None),
+        CredentialsKind::Basic => ("basic", None, None),
+        CredentialsKind::ApiKey { location, name } => {
+            let location = match location {
+                ApiKeyLocation::Header => "header",
+                ApiKeyLocation::Query => "query",
+                ApiKeyLocation::Cookie => "cookie",
+            };
+            ("api_key", Some(location.to_string()), Some(name.clone()))
+        }
+    };
+
+    templates::RustSecurityScheme {
+        doc: scheme.doc.clone(),
+        title: name.to_string(),
+        kind: kind.to_string(),
+        location,
+        key_name,
+        authenticate: authenticate_fn(name),
+    }
+}
+
+/// Argument of the handler with the credentials of the satisfied security requirement,
+/// checked by the `Authenticator` implemented for the given type parameter
+pub fn method_auth(
+    name: &str,
+    op: &RustOperation,
+    authenticator: &str,
+) -> Option<templates::RustMethodAuth> {
+    if op.security.is_empty() {
+        return None;
+    }
+
+    let schemes: IndexSet<&String> = op.security.iter().flat_map(|x| x.keys()).collect();
+    let anonymous = op.security.iter().any(|x| x.is_empty());
+
+    let credentials = match schemes.len() {
+        1 => None,
+        _ => Some(templates::RustCredentials {
+            title: format!("{}Credentials", to_rust_identifier(name, Case::UpperCamel)),
+            operation_id: name.to_string(),
+            fields: schemes
+                .iter()
+                .map(|scheme| templates::RustCredentialsField {
+                    name: scheme.to_case(Case::Snake),
+                    type_: scheme.to_string(),
+                })
+                .collect(),
+        }),
+    };
+
+    // Expression of the argument value when credentials of the given schemes are accepted
+    let value = |accepted: &[&String]| match &credentials {
+        Some(credentials) => {
+            let fields: Vec<String> = schemes
+                .iter()
+                .map(|scheme| match accepted.contains(scheme) {
+                    true => format!(
+                        "{}: Some({}.clone())",
+                        scheme.to_case(Case::Snake),
+                        credentials_var(scheme)
+                    ),
+                    false => format!("{}: None", scheme.to_case(Case::Snake)),
+                })
+                .collect();
+            format!("{} {{ {} }}", credentials.title, fields.join(", "))
+        }
+        None => match (accepted, anonymous) {
+            ([scheme], false) => format!("{}.clone()", credentials_var(scheme)),
+            ([scheme], true) => format!("Some({}.clone())", credentials_var(scheme)),
+            _ => "None".to_string(),
+        },
+    };
+
+    let mut lines = Vec::new();
+
+    for scheme in &schemes {
+        lines.push(format!(
+            "let {} = {scheme}::from_request(&req);",
+            credentials_var(scheme)
+        ));
+    }
+
+    lines.push("let mut rejection = None;".to_string());
+    lines.push("let auth = 'auth: {".to_string());
+
+    for requirement in &op.security {
+        if requirement.is_empty() {
+            continue;
+        }
+
+        let vars: Vec<String> = requirement.keys().map(|x| credentials_var(x)).collect();
+        let somes: Vec<String> = vars.iter().map(|x| format!("Some({x})")).collect();
+        let refs: Vec<String> = vars.iter().map(|x| format!("&{x}")).collect();
+
+        match vars.len() {
+            1 => lines.push(format!("    if let {} = {} {{", somes[0], refs[0])),
+            _ => lines.push(format!(
+                "    if let ({}) = ({}) {{",
+                somes.join(", "),
+                refs.join(", ")
+            )),
+        }
+
+        for (index, (scheme, scopes)) in requirement.iter().enumerate() {
+            let scopes: Vec<String> = scopes.iter().map(|x| quote_str(x)).collect();
+            let call = format!(
+                "{authenticator}::{}(&data, {}, &[{}]).await",
+                authenticate_fn(scheme),
+                credentials_var(scheme),
+                scopes.join(", ")
+            );
+
+            match index {
+                0 if requirement.len() == 1 => lines.push(format!("        let result = {call};")),
+                0 => lines.push(format!("        let mut result = {call};")),
+                _ => {
+                    lines.push("        if result.is_ok() {".to_string());
+                    lines.push(format!("            result = {call};"));
+                    lines.push("        }".to_string());
+                }
+            }
+        }
+
+        let accepted: Vec<&String> = requirement.keys().collect();
+
+        lines.push("        match result {".to_string());
+        lines.push(format!(
+            "            Ok(()) => break 'auth {},",
+            value(&accepted)
+        ));
+        lines.push("            Err(error) => rejection = Some(error),".to_string());
+        lines.push("        }".to_string());
+        lines.push("    }".to_string());
+    }
+
+    // Rejected callers are challenged with every scheme the operation accepts
+    let challenges: Vec<String> = schemes
+        .iter()
+        .map(|scheme| format!("{scheme}::CHALLENGE"))
+        .collect();
+    let challenges = format!("&[{}]", challenges.join(", "));
+
+    let missing = match anonymous {
+        true => value(&[]),
+        false => format!("return Err(AuthError::missing().challenge({challenges}))"),
+    };
+
+    lines.push("    match rejection {".to_string());
+    lines.push(format!(
+        "        Some(error) => return Err(error.challenge({challenges})),"
+    ));
+    lines.push(format!("        None => {missing},"));
+    lines.push("    }".to_string());
+    lines.push("};".to_string());
+
+    let type_ = match &credentials {
+        Some(credentials) => credentials.title.clone(),
+        None => match anonymous {
+            true => format!("Option<{}>", schemes[0]),
+            false => schemes[0].to_string(),
+        },
+    };
+
+    Some(templates::RustMethodAuth {
+        type_,
+        authenticate: lines.join("\n"),
+        credentials,
+    })
+}
@@ -0,0 +1,234 @@
+//! Changes to the specs served by the generated api, describing what it actually responds
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use openapiv3::{Components, OpenAPI, Operation, PathItem, ReferenceOr, Response, StatusCode};
+use serde_json::{json, Value};
+
+use super::{models::types::is_success, ApiOptions};
+
+fn operations_mut(path_item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut path_item.get,
+        &mut path_item.put,
+        &mut path_item.post,
+        &mut path_item.delete,
+        &mut path_item.options,
+        &mut path_item.head,
+        &mut path_item.patch,
+        &mut path_item.trace,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn add_schemas(components: &mut Components, schemas: Vec<(&str, Value)>) -> Result<()> {
+    for (name, schema) in schemas {
+        if !components.schemas.contains_key(name) {
+            components.schemas.insert(
+                name.to_string(),
+                ReferenceOr::Item(serde_json::from_value(schema)?),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace error content with problem details whose title is the error message
+fn to_problem_response(response: &mut Response) -> Result<()> {
+    let Some((_, media)) = response.content.first() else {
+        return Ok(());
+    };
+
+    let title = match &media.schema {
+        Some(schema) => serde_json::to_value(schema)?,
+        None => json!({"type": "string"}),
+    };
+
+    let problem = serde_json::from_value(json!({
+        "schema": {
+            "allOf": [
+                {"$ref": "#/components/schemas/ProblemDetails"},
+                {"type": "object", "properties": {"title": title}}
+            ]
+        }
+    }))?;
+
+    response.content.clear();
+    response
+        .content
+        .insert("application/problem+json".to_string(), problem);
+
+    Ok(())
+}
+
+/// Describe error responses as `application/problem+json` rendered by `Detailed`
+fn document_problems(spec: &mut OpenAPI) -> Result<bool> {
+    let mut referenced = HashSet::new();
+    let mut documented = false;
+
+    for path_item in spec.paths.paths.values_mut() {
+        let ReferenceOr::Item(path_item) = path_item else {
+            continue;
+        };
+
+        for operation in operations_mut(path_item) {
+            for (status, response) in &mut operation.responses.responses {
+                if is_success(status) {
+                    continue;
+                }
+
+                match response {
+                    ReferenceOr::Item(response) => to_problem_response(response)?,
+                    ReferenceOr::Reference { reference } => {
+                        if let Some(name) = reference.strip_prefix("#/components/responses/") {
+                            referenced.insert(name.to_string());
+                        }
+                    }
+                }
+
+                documented = true;
+            }
+        }
+    }
+
+    if !documented {
+        return Ok(false);
+    }
+
+    let components = spec.components.get_or_insert_with(Default::default);
+
+    for name in referenced {
+        if let Some(ReferenceOr::Item(response)) = components.responses.get_mut(&name) {
+            to_problem_response(response)?;
+        }
+    }
+
+    add_schemas(
+        components,
+        vec![(
+            "ProblemDetails",
+            json!({
+                "type": "object",
+                "description": "Details of the error as described by RFC 7807",
+                "required": ["type", "title", "status", "detail"],
+                "properties": {
+                    "type": {
+                        "type": "string",
+                        "description": "URI reference identifying the error"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Error message declared in the spec"
+                    },
+                    "status": {"type": "integer"},
+                    "detail": {
+                        "type": "string",
+                        "description": "Explanation of this occurrence of the error"
+                    },
+                    "instance": {
+                        "type": "string",
+                        "description": "URI reference identifying this occurrence of the error"
+                    }
+                }
+            }),
+        )],
+    )?;
+
+    Ok(true)
+}
+
+/// Add 422 response to every operation that takes arguments
+fn document_request_errors(spec: &mut OpenAPI) -> Result<()> {
+    let components = spec.components.get_or_insert_with(Default::default);
+
+    add_schemas(
+        components,
+        vec![
+            (
+                "FieldError",
+                json!({
+                    "type": "object",
+                    "description": "Invalid value of the request argument",
+                    "required": ["field", "reason"],
+                    "properties": {
+                        "field": {
+                            "type": "string",
+                            "description": "Path to the invalid value like `body.items[0].name`"
+                        },
+                        "reason": {"type": "string"}
+                    }
+                }),
+            ),
+            (
+                "ValidationErrors",
+                json!({
+                    "type": "object",
+                    "required": ["errors"],
+                    "properties": {
+                        "errors": {
+                            "type": "array",
+                            "items": {"$ref": "#/components/schemas/FieldError"}
+                        }
+                    }
+                }),
+            ),
+        ],
+    )?;
+
+    let response: ReferenceOr<Response> = ReferenceOr::Item(serde_json::from_value(json!({
+        "description": "Request arguments could not be parsed or are invalid",
+        "content": {
+            "application/json": {
+                "schema": {"$ref": "#/components/schemas/ValidationErrors"}
+            }
+        }
+    }))?);
+
+    for path_item in spec.paths.paths.values_mut() {
+        let ReferenceOr::Item(path_item) = path_item else {
+            continue;
+        };
+
+        let path_parameters = !path_item.parameters.is_empty();
+
+        for operation in operations_mut(path_item) {
+            if !path_parameters
+                && operation.parameters.is_empty()
+                && operation.request_body.is_none()
+            {
+                continue;
+            }
+
+            operation
+                .responses
+                .responses
+                .entry(StatusCode::Code(422))
+                .or_insert_with(|| response.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Content of the spec to serve instead of the original one, if it differs
+pub fn served_spec(spec: &OpenAPI, options: &ApiOptions) -> Result<Option<String>> {
+    let mut spec = spec.clone();
+
+    let mut changed = document_problems(&mut spec).context("Could not document problems")?;
+
+    if options.document_errors {
+        document_request_errors(&mut spec).context("Could not document request errors")?;
+        changed = true;
+    }
+
+    if !changed {
+        return Ok(None);
+    }
+
+    let content = serde_yaml::to_string(&spec).context("Could not serialize spec")?;
+
+    Ok(Some(content))
+}
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::Serialize;
 use tera::{Tera, Value};
 
@@ -9,6 +13,32 @@ static T_ENUM: &str = include_str!("static/enum.tera");
 static T_STRUCT: &str = include_str!("static/struct.tera");
 static T_DEFAULT: &str = include_str!("static/default.tera");
 static T_ERROR: &str = include_str!("static/error.tera");
+static T_MEDIA: &str = include_str!("static/media.tera");
+static T_BODY: &str = include_str!("static/body.tera");
+static T_CLIENT: &str = include_str!("static/client.tera");
+static T_STUB: &str = include_str!("static/stub.tera");
+static T_DOCS: &str = include_str!("static/docs.html");
+static T_VALIDATE: &str = include_str!("static/validate.tera");
+static T_SECURITY: &str = include_str!("static/security.tera");
+
+/// Version of the contexts the templates are rendered with, like `RustModule`.
+/// It is increased on every change of them that breaks the existing custom templates
+pub const CONTEXT_VERSION: u32 = 1;
+
+/// Built-in templates by file name, any of them can be overridden by a custom template
+pub static TEMPLATES: [(&str, &str); 11] = [
+    ("enum.tera", T_ENUM),
+    ("error.tera", T_ERROR),
+    ("struct.tera", T_STRUCT),
+    ("default.tera", T_DEFAULT),
+    ("media.tera", T_MEDIA),
+    ("validate.tera", T_VALIDATE),
+    ("security.tera", T_SECURITY),
+    ("api.tera", T_API),
+    ("body.tera", T_BODY),
+    ("client.tera", T_CLIENT),
+    ("stub.tera", T_STUB),
+];
 
 #[derive(Debug, Serialize)]
 pub struct RustEnumVariant {
@@ -52,6 +82,8 @@ pub struct RustErrorVariant {
     pub title: String,
     pub status: String,
     pub display: String,
+    /// URI reference identifying the variant in problem details
+    pub problem_type: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -61,10 +93,81 @@ pub struct RustError {
     pub variants: Vec<RustErrorVariant>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RustMediaType {
+    pub media_type: String,
+    pub format: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustMediaTypes {
+    pub title: String,
+    pub media_types: Vec<RustMediaType>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustValidation {
+    pub title: String,
+    /// Blocks checking constraints of struct fields
+    pub fields: Vec<String>,
+    /// Enum variants holding data with constraints
+    pub variants: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustSecurityScheme {
+    pub doc: Option<String>,
+    pub title: String,
+    /// How the credentials are sent: `bearer`, `basic` or `api_key`
+    pub kind: String,
+    /// Where the api key is sent: `header`, `query` or `cookie`
+    pub location: Option<String>,
+    /// Name of the header, query parameter or cookie holding the api key
+    pub key_name: Option<String>,
+    /// Method of `Authenticator` checking the credentials
+    pub authenticate: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustCredentialsField {
+    pub name: String,
+    pub type_: String,
+}
+
+/// Credentials of the operation accepting several security schemes
+#[derive(Debug, Serialize)]
+pub struct RustCredentials {
+    pub title: String,
+    pub operation_id: String,
+    pub fields: Vec<RustCredentialsField>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustMethodAuth {
+    pub type_: String,
+    /// Statements authenticating the request into `auth` variable
+    pub authenticate: String,
+    pub credentials: Option<RustCredentials>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RustMethodArg {
     pub name: String,
     pub type_: String,
+    /// Type the mock service records the argument as
+    pub record_type: String,
+    /// Expression converting the argument into the recorded type
+    pub record: String,
+    /// Statement collecting constraint violations of the argument
+    pub validate: Option<String>,
+    /// Expression checking items of the streamed argument as they arrive
+    pub validate_stream: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustExtractor {
+    pub name: String,
+    pub type_: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -72,7 +175,35 @@ pub struct RustMethod {
     pub operation_id: String,
     pub doc: Option<String>,
     pub response_type: String,
+    /// Type returned by the method, `Result` of the response when the body is streamed
+    pub return_type: String,
+    /// Method propagates errors of reading the streamed body
+    pub fallible: bool,
     pub args: Vec<RustMethodArg>,
+    /// Struct with arguments of the call recorded by the mock service
+    pub call_type: String,
+    /// Arguments are validated before calling the service
+    pub validated: bool,
+    /// Items of the streamed body are validated while the service reads them
+    pub validated_stream: bool,
+    /// Credentials the caller is authenticated with before calling the service
+    pub auth: Option<RustMethodAuth>,
+    /// Arguments extracted from the request, not recorded by the mock service
+    pub extractors: Vec<RustExtractor>,
+    /// Trait of the api service declaring the method
+    pub service: String,
+    /// Type parameter of `make_scope` implementing the trait
+    pub implementor: String,
+    pub tags: Vec<String>,
+}
+
+/// Trait of the api service declaring some of its methods
+#[derive(Debug, Serialize)]
+pub struct RustService {
+    pub doc: Option<String>,
+    pub title: String,
+    /// Type parameter of `make_scope` implementing the trait
+    pub implementor: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -80,12 +211,31 @@ pub struct MethodPath {
     pub operation_id: String,
     pub path: String,
     pub method: String,
+    /// Function handling the route
+    pub handler: String,
+    /// Type parameter of `make_scope` whose `wrap_route` hook wraps the route
+    pub implementor: String,
+    /// Quoted tags of the operation passed to the hook
+    pub tags: Vec<String>,
+    /// Maximum length of the buffered request body
+    pub payload_limit: Option<usize>,
+}
+
+/// Routes of the operations sharing the first tag, added by the `configure_tag` hook
+#[derive(Debug, Serialize)]
+pub struct TagGroup {
+    pub tag: String,
+    /// Type parameter of `make_scope` whose `configure_tag` hook adds the routes
+    pub implementor: String,
+    pub paths: Vec<MethodPath>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct StaticInclude {
     pub title: String,
     pub file_path: String,
+    /// Content embedded instead of including the file
+    pub content: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -113,19 +263,110 @@ pub struct StaticService {
     pub target: String,
 }
 
+/// Context of `api.tera`. Custom templates rely on it, so its fields are only changed
+/// along with `CONTEXT_VERSION`
 #[derive(Debug, Serialize)]
 pub struct RustModule {
+    /// Enums of the models
     pub enums: Vec<RustEnum>,
+    /// Structs of the models, including the path and query arguments of the operations
     pub structs: Vec<RustStruct>,
+    /// Functions providing default values of the fields
     pub defaults: Vec<RustDefault>,
+    /// Error responses of the operations
     pub errors: Vec<RustError>,
+    /// Types of request bodies accepting several media types
+    pub media_types: Vec<RustMediaTypes>,
+    /// Models checking their constraints
+    pub validations: Vec<RustValidation>,
+    /// Models or request arguments check constraints, so the checking functions are generated
+    pub validation: bool,
+    pub security_schemes: Vec<RustSecurityScheme>,
+    /// Any of the constraints is a regular expression
+    pub patterns: bool,
+    /// Request body formats used by any of the media types
+    pub body_formats: Vec<String>,
+    /// Any of the request bodies is newline delimited json
+    pub ndjson: bool,
+    /// Any of the responses is a stream of server-sent events
+    pub event_streams: bool,
+    /// Methods of the api service, one per operation
     pub methods: Vec<RustMethod>,
-    pub paths: Vec<MethodPath>,
+    /// Routes of the operations grouped by their first tag
+    pub tag_groups: Vec<TagGroup>,
+    /// Routes of the docs and specs served next to the api
     pub redirects: Vec<StaticRedirect>,
     pub static_includes: Vec<StaticInclude>,
     pub static_strings: Vec<StaticString>,
     pub static_htmls: Vec<StaticHtml>,
     pub static_services: Vec<StaticService>,
+    /// Generate mock implementation of the api service
+    pub mock: bool,
+    /// Metrics middleware taken by `make_scope`: `none`, `prometheus` or `middleware`
+    pub metrics: String,
+    /// Wrap every operation route in a tracing span
+    pub tracing: bool,
+    /// Traits of the api service declaring the methods
+    pub services: Vec<RustService>,
+    /// Services are split by tags, authenticated by their own `make_scope` type parameter
+    pub split_by_tag: bool,
+    /// Path of `Detailed` used by the exported macros, like `$crate::server::api::Detailed`
+    pub detailed_path: String,
+    /// Module is `include!`d into another file, so it cannot have inner attributes
+    pub included: bool,
+    /// Derives added to the models, like `Hash`
+    pub derives: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocsPage {
+    /// Application name
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientBody {
+    pub arg_type: String,
+    pub encoding: String,
+    pub media_type: String,
+    pub optional: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientMethod {
+    pub operation_id: String,
+    pub doc: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub path_type: Option<String>,
+    pub query_type: Option<String>,
+    pub body: Option<ClientBody>,
+    pub response_type: String,
+    /// Response is returned as is, without deserializing
+    pub streaming: bool,
+    pub error_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientModule {
+    pub api: RustModule,
+    pub methods: Vec<ClientMethod>,
+    /// Body encodings used by any of the methods
+    pub encodings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StubModule {
+    /// Type the api service is implemented for
+    pub server: String,
+    pub methods: Vec<RustMethod>,
+    /// Methods of `Authenticator` to implement
+    pub authenticators: Vec<RustSecurityScheme>,
+    /// Wrap methods into the `impl` block, otherwise only methods are rendered
+    pub impl_block: bool,
+    pub services: Vec<RustService>,
+    /// Methods are declared by several traits, one per tag
+    pub split_by_tag: bool,
 }
 
 pub fn quote_str(value: &str) -> String {
@@ -209,7 +450,71 @@ fn indent(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
     Ok(Value::String(result))
 }
 
-pub fn render_rust_module(module: RustModule) -> Result<String> {
+/// First line of the dumped templates telling the context version they are written for
+pub fn version_line() -> String {
+    format!("{{#- context version {CONTEXT_VERSION} -#}}")
+}
+
+/// Custom templates of the directory, which are the files with the names of built-in templates.
+/// Other `.tera` files are reported, as they would be silently ignored otherwise
+pub fn custom_templates(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    let entries =
+        read_dir(dir).with_context(|| format!("Could not read {}", dir.to_string_lossy()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+
+        if !path.is_file() || path.extension().is_none_or(|x| x != "tera") {
+            continue;
+        }
+
+        let known = TEMPLATES
+            .iter()
+            .any(|(name, _)| path.file_name().is_some_and(|x| x == *name));
+
+        if !known {
+            let names: Vec<&str> = TEMPLATES.iter().map(|(name, _)| *name).collect();
+            bail!(
+                "Unknown template {}, custom templates are named as the built-in ones: {}",
+                path.to_string_lossy(),
+                names.join(", ")
+            );
+        }
+
+        paths.push(path);
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Reads the custom template, checking that it is written for the current context version
+fn read_custom_template(path: &Path) -> Result<String> {
+    let content = read_to_string(path)?;
+
+    let version = content
+        .lines()
+        .next()
+        .and_then(|line| line.trim().strip_prefix("{#- context version "))
+        .and_then(|rest| rest.strip_suffix("-#}"))
+        .map(|version| version.trim().parse::<u32>());
+
+    match version {
+        None => {}
+        Some(Ok(CONTEXT_VERSION)) => {}
+        Some(Ok(version)) => bail!(
+            "Template is written for context version {version}, but the generator renders version {CONTEXT_VERSION}. Dump the built-in templates with --dump-templates and port the changes to them"
+        ),
+        Some(Err(error)) => bail!("Invalid context version: {error}"),
+    }
+
+    Ok(content)
+}
+
+fn make_tera(templates: Option<&Path>) -> Result<Tera> {
     let mut tera = Tera::default();
 
     tera.register_filter("quote", quote);
@@ -217,13 +522,62 @@ pub fn render_rust_module(module: RustModule) -> Result<String> {
     tera.register_filter("indent", indent);
     tera.register_filter("newline", newline);
 
-    tera.add_raw_template("enum.tera", T_ENUM)?;
-    tera.add_raw_template("error.tera", T_ERROR)?;
-    tera.add_raw_template("struct.tera", T_STRUCT)?;
-    tera.add_raw_template("default.tera", T_DEFAULT)?;
-    tera.add_raw_template("api.tera", T_API)?;
+    let mut sources: Vec<(String, String)> = TEMPLATES
+        .iter()
+        .map(|(name, content)| (name.to_string(), content.to_string()))
+        .collect();
+
+    if let Some(dir) = templates {
+        for path in custom_templates(dir)? {
+            let content = read_custom_template(&path)
+                .with_context(|| format!("Could not load template {}", path.to_string_lossy()))?;
+
+            for (name, source) in &mut sources {
+                if path.file_name().is_some_and(|x| x == name.as_str()) {
+                    *source = content.clone();
+                }
+            }
+        }
+    }
+
+    sources.push(("docs.html".to_string(), T_DOCS.to_string()));
+
+    tera.add_raw_templates(sources)
+        .context("Could not parse templates")?;
+
+    Ok(tera)
+}
+
+pub fn render_rust_module(module: RustModule, templates: Option<&Path>) -> Result<String> {
+    let tera = make_tera(templates)?;
 
     let ctx = tera::Context::from_serialize(module)?;
 
     Ok(tera.render("api.tera", &ctx)?)
 }
+
+pub fn render_client_module(module: ClientModule, templates: Option<&Path>) -> Result<String> {
+    let tera = make_tera(templates)?;
+
+    let ctx = tera::Context::from_serialize(module)?;
+
+    Ok(tera.render("client.tera", &ctx)?)
+}
+
+pub fn render_stub_module(module: StubModule, templates: Option<&Path>) -> Result<String> {
+    let tera = make_tera(templates)?;
+
+    let ctx = tera::Context::from_serialize(module)?;
+
+    let rendered = tera.render("stub.tera", &ctx)?;
+
+    Ok(rendered.trim_start_matches('\n').to_string())
+}
+
+pub fn render_docs(page: DocsPage) -> Result<String> {
+    let tera = make_tera(None)?;
+
+    let ctx = tera::Context::from_serialize(page)?;
+
+    Ok(tera.render("docs.html", &ctx)?)
+}
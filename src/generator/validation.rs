@@ -0,0 +1,343 @@
+//! Code checking constraints declared in the schema at runtime
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use super::{
+    models::types::{
+        Bound, Constraints, Definition, DefinitionData, InlineType, REnum, RStruct, RustOperation,
+    },
+    templates::{self, quote_str},
+};
+
+fn references_validated(type_: &InlineType, validated: &HashSet<String>) -> bool {
+    type_.any(&|x| matches!(x, InlineType::Reference(name) if validated.contains(name)))
+}
+
+/// Names of definitions holding values with constraints, directly or in nested definitions
+pub fn validated_definitions(definitions: &IndexMap<String, Definition>) -> HashSet<String> {
+    let mut validated = HashSet::new();
+
+    loop {
+        let mut found = Vec::new();
+
+        for (name, def) in definitions {
+            if validated.contains(name) {
+                continue;
+            }
+
+            let has_constraints = match &def.data {
+                DefinitionData::Struct(value) => value.properties.iter().any(|prop| {
+                    !prop.constraints.is_empty() || references_validated(&prop.type_, &validated)
+                }),
+                DefinitionData::Enum(value) => value.variants.iter().any(|variant| {
+                    variant
+                        .data
+                        .as_ref()
+                        .is_some_and(|data| references_validated(data, &validated))
+                }),
+                _ => false,
+            };
+
+            if has_constraints {
+                found.push(name.clone());
+            }
+        }
+
+        if found.is_empty() {
+            return validated;
+        }
+
+        validated.extend(found);
+    }
+}
+
+fn has_pattern(constraints: &Constraints) -> bool {
+    constraints.pattern.is_some() || constraints.items.as_deref().is_some_and(has_pattern)
+}
+
+/// Any of the definitions or request bodies has constraint requiring regular expressions
+pub fn uses_patterns(
+    definitions: &IndexMap<String, Definition>,
+    operations: &IndexMap<String, RustOperation>,
+) -> bool {
+    let definition_patterns = definitions.values().any(|def| match &def.data {
+        DefinitionData::Struct(value) => value
+            .properties
+            .iter()
+            .any(|prop| has_pattern(&prop.constraints)),
+        _ => false,
+    });
+
+    definition_patterns
+        || operations
+            .values()
+            .any(|op| has_pattern(&op.body_constraints))
+}
+
+fn indent(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| format!("    {line}"))
+        .collect()
+}
+
+fn option_literal(value: Option<usize>) -> String {
+    match value {
+        Some(value) => format!("Some({value})"),
+        None => "None".to_string(),
+    }
+}
+
+fn bound_literal(bound: &Option<Bound>) -> String {
+    match bound {
+        Some(bound) => format!("Some(({}, {}))", bound.value, bound.exclusive),
+        None => "None".to_string(),
+    }
+}
+
+/// Statements checking constraints of the `value` reference with the `path` in errors
+fn value_checks(type_: &InlineType, constraints: &Constraints) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    match type_ {
+        InlineType::Option(inner) => {
+            let inner_lines = value_checks(inner, constraints);
+            if !inner_lines.is_empty() {
+                lines.push("if let Some(value) = value {".to_string());
+                lines.extend(indent(inner_lines));
+                lines.push("}".to_string());
+            }
+        }
+        InlineType::String => {
+            if constraints.min_length.is_some() || constraints.max_length.is_some() {
+                lines.push(format!(
+                    "check_length(&path, value, {}, {}, errors);",
+                    option_literal(constraints.min_length),
+                    option_literal(constraints.max_length),
+                ));
+            }
+            if let Some(pattern) = &constraints.pattern {
+                lines.push(
+                    "static PATTERN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();"
+                        .to_string(),
+                );
+                lines.push(format!(
+                    "check_pattern(&path, value, &PATTERN, {}, errors);",
+                    quote_str(pattern)
+                ));
+            }
+            if let Some(format) = &constraints.format {
+                lines.push(format!(
+                    "check_format(&path, value, {}, errors);",
+                    quote_str(format)
+                ));
+            }
+        }
+        InlineType::Integer | InlineType::Float => {
+            if constraints.minimum.is_some() || constraints.maximum.is_some() {
+                lines.push(format!(
+                    "check_range(&path, *value, {}, {}, errors);",
+                    bound_literal(&constraints.minimum),
+                    bound_literal(&constraints.maximum),
+                ));
+            }
+            if let Some(divisor) = &constraints.multiple_of {
+                let check = match type_ {
+                    InlineType::Integer => "check_multiple_of_int",
+                    _ => "check_multiple_of_float",
+                };
+                lines.push(format!("{check}(&path, *value, {divisor}, errors);"));
+            }
+        }
+        InlineType::Array(item) => {
+            if constraints.min_items.is_some()
+                || constraints.max_items.is_some()
+                || constraints.unique_items
+            {
+                lines.push(format!(
+                    "check_items(&path, value, {}, {}, {}, errors);",
+                    option_literal(constraints.min_items),
+                    option_literal(constraints.max_items),
+                    constraints.unique_items,
+                ));
+            }
+            if let Some(items) = &constraints.items {
+                let item_lines = value_checks(item, items);
+                if !item_lines.is_empty() {
+                    lines.push("for (index, value) in value.iter().enumerate() {".to_string());
+                    lines.push("    let path = format!(\"{path}[{index}]\");".to_string());
+                    lines.extend(indent(item_lines));
+                    lines.push("}".to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+
+    lines
+}
+
+pub fn struct_validation(
+    name: &str,
+    struct_def: &RStruct,
+    validated: &HashSet<String>,
+) -> templates::RustValidation {
+    let mut fields = Vec::new();
+
+    for prop in &struct_def.properties {
+        let mut lines = value_checks(&prop.type_, &prop.constraints);
+
+        if references_validated(&prop.type_, validated) {
+            lines.push("value.validate_into(&path, errors);".to_string());
+        }
+
+        if lines.is_empty() {
+            continue;
+        }
+
+        let mut block = vec![
+            "{".to_string(),
+            format!(
+                "    let path = field_path(path, {});",
+                quote_str(&prop.rename)
+            ),
+            format!("    let value = &self.{};", prop.name),
+        ];
+        block.extend(indent(lines));
+        block.push("}".to_string());
+
+        fields.push(block.join("\n"));
+    }
+
+    templates::RustValidation {
+        title: name.to_string(),
+        fields,
+        variants: Vec::new(),
+    }
+}
+
+pub fn enum_validation(
+    name: &str,
+    enum_def: &REnum,
+    validated: &HashSet<String>,
+) -> templates::RustValidation {
+    let mut variants = Vec::new();
+
+    for variant in &enum_def.variants {
+        if let Some(data) = &variant.data {
+            if references_validated(data, validated) {
+                variants.push(variant.name.clone());
+            }
+        }
+    }
+
+    templates::RustValidation {
+        title: name.to_string(),
+        fields: Vec::new(),
+        variants,
+    }
+}
+
+/// Statements checking constraints of the `value` and of the definitions it references
+fn body_checks(
+    type_: &InlineType,
+    constraints: &Constraints,
+    validated: &HashSet<String>,
+) -> Vec<String> {
+    let mut lines = value_checks(type_, constraints);
+
+    if references_validated(type_, validated) {
+        lines.push("value.validate_into(&path, errors);".to_string());
+    }
+
+    lines
+}
+
+/// Block collecting constraint violations of the body argument holding the value of the type
+fn body_validation(
+    name: &str,
+    type_: &InlineType,
+    constraints: &Constraints,
+    validated: &HashSet<String>,
+) -> Option<String> {
+    let lines = body_checks(type_, constraints, validated);
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut block = vec![
+        "{".to_string(),
+        format!("    let path = {};", quote_str(name)),
+        format!("    let value: &{type_} = &{name};"),
+        "    let errors = &mut errors;".to_string(),
+    ];
+    block.extend(indent(lines));
+    block.push("}".to_string());
+
+    Some(block.join("\n"))
+}
+
+/// Statement collecting constraint violations of the handler argument.
+/// Constraints are the ones of the request body schema itself
+pub fn arg_validation(
+    name: &str,
+    type_: &InlineType,
+    constraints: &Constraints,
+    validated: &HashSet<String>,
+) -> Option<String> {
+    match type_ {
+        InlineType::Path(inner)
+        | InlineType::Query(inner)
+        | InlineType::Json(inner)
+        | InlineType::Negotiated(inner, _)
+            if constraints.is_empty() && references_validated(inner, validated) =>
+        {
+            Some(format!(
+                "{name}.validate_into({}, &mut errors);",
+                quote_str(name)
+            ))
+        }
+        InlineType::Json(inner) | InlineType::Negotiated(inner, _) => {
+            body_validation(name, inner, constraints, validated)
+        }
+        InlineType::String => body_validation(name, type_, constraints, validated),
+        InlineType::Option(inner) => {
+            let check = arg_validation(name, inner, constraints, validated)?;
+            Some(format!(
+                "if let Some({name}) = &{name} {{\n{}\n}}",
+                indent(check.lines().map(|x| x.to_string()).collect()).join("\n")
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Expression checking every item of the streamed handler argument as it arrives
+pub fn stream_validation(
+    name: &str,
+    type_: &InlineType,
+    constraints: &Constraints,
+    validated: &HashSet<String>,
+) -> Option<String> {
+    match type_ {
+        InlineType::NdJson(item) => {
+            let lines = body_checks(item, constraints, validated);
+            if lines.is_empty() {
+                return None;
+            }
+
+            let mut block = vec![format!("{name}.checked(|value, path, errors| {{")];
+            block.extend(indent(lines));
+            block.push("})".to_string());
+
+            Some(block.join("\n"))
+        }
+        InlineType::Option(inner) => {
+            let check = stream_validation(name, inner, constraints, validated)?;
+            Some(format!("{name}.map(|{name}| {check})"))
+        }
+        _ => None,
+    }
+}
@@ -2,11 +2,34 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+mod builder;
 mod generator;
 use anyhow::Result;
-pub use generator::OpenapiWithMeta;
+pub use builder::Builder;
+pub use generator::{
+    custom_templates, dump_templates, generate_docs, module_path, parse_module_path,
+    parse_type_mapping, ApiOptions, Extractor, Metrics, OpenapiWithMeta,
+};
 mod openapictx;
+mod specs;
 
-pub fn generate_api(docs_path: &str, specs: &[OpenapiWithMeta]) -> Result<(String, String)> {
-    generator::generate_api(docs_path, specs)
+pub fn generate_api(
+    docs_path: &str,
+    specs: &[OpenapiWithMeta],
+    options: &ApiOptions,
+) -> Result<(String, String)> {
+    generator::generate_api(docs_path, specs, options)
+}
+
+pub fn generate_client(specs: &[OpenapiWithMeta], options: &ApiOptions) -> Result<String> {
+    generator::generate_client(specs, options)
+}
+
+pub fn generate_stub(
+    specs: &[OpenapiWithMeta],
+    server: &str,
+    implementation: Option<&str>,
+    options: &ApiOptions,
+) -> Result<String> {
+    generator::generate_stub(specs, server, implementation, options)
 }
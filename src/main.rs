@@ -2,45 +2,191 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+mod config;
 mod generator;
 mod openapictx;
+mod specs;
 
 use std::{
+    collections::HashMap,
     env,
     fs::{read_dir, read_to_string},
-    io::Write,
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use serde::Serialize;
-use tera::Tera;
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use similar::TextDiff;
+use specs::{is_doc_page, DEFAULT_TITLE};
 
 /// Simple program to greet a person
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Path to the source openapi file
-    spec_dir: PathBuf,
-    out_path: PathBuf,
+    /// Directory of the `openapi*.yaml` specs and docs.html, may be left out with `--spec`
+    spec_dir: Option<PathBuf>,
+    out_path: Option<PathBuf>,
+    /// Generate from the given spec file or glob pattern, instead of `openapi*.yaml` files
+    /// of the spec directory
+    #[arg(long, value_name = "PATH")]
+    spec: Vec<String>,
+    /// Skip spec files matching the glob pattern, by their name or by their path when it has `/`
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+    /// Also generate typed http client of the api into the given file
+    #[arg(long)]
+    client: Option<PathBuf>,
+    /// Also generate `mock::MockApiService` with programmable responses for tests
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    mock: Option<bool>,
+    /// Document 422 response with invalid request arguments in the served openapi specs
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    document_errors: Option<bool>,
+    /// Middleware collecting metrics taken by the generated `make_scope`
+    #[arg(long, value_enum)]
+    metrics: Option<generator::Metrics>,
+    /// Handle requests of every operation inside of a `tracing` span named by its operationId
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    tracing: Option<bool>,
+    /// Append argument extracted from the request to every method of the api service,
+    /// the type implements `FromRequest` like `HttpRequest` or `web::ReqData<User>`
+    #[arg(long, value_name = "[NAME=]TYPE")]
+    extractor: Vec<generator::Extractor>,
+    /// Add derive to the generated structs and enums, like `Hash` or `schemars::JsonSchema`
+    #[arg(long, value_name = "PATH")]
+    derive: Vec<String>,
+    /// Use the rust type for the string, integer and number schemas of the format,
+    /// like `uuid=uuid::Uuid` or `date-time=chrono::DateTime<chrono::Utc>`
+    #[arg(long, value_name = "FORMAT=TYPE", value_parser = generator::parse_type_mapping)]
+    type_map: Vec<(String, String)>,
+    /// Declare operations in one trait per their first tag, like `QuotaApi`, instead of `ApiService`
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    split_by_tag: Option<bool>,
+    /// Path of the generated module in its crate used by the exported `apibail!` and `detailed!`
+    /// macros, like `server::api`. Derived from the output path inside of `src` by default
+    #[arg(long, value_name = "PATH", value_parser = generator::parse_module_path)]
+    module_path: Option<String>,
+    /// Create docs.html in the spec directory without asking when it is missing
+    #[arg(short, long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    yes: Option<bool>,
+    /// Application name shown on the docs page created when docs.html is missing
+    #[arg(long, value_name = "NAME")]
+    title: Option<String>,
+    /// Never create docs.html, embed the docs page into the generated code when it is missing
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", conflicts_with = "yes")]
+    no_docs: Option<bool>,
+    /// Render with the templates of the directory overriding the built-in ones by file name
+    #[arg(long, value_name = "DIR")]
+    templates: Option<PathBuf>,
+    /// Write the built-in templates into the directory for editing, instead of generating
+    #[arg(long, value_name = "DIR", exclusive = true)]
+    dump_templates: Option<PathBuf>,
+    /// Do not write anything, fail with the diff when the generated files are not up to date
+    #[arg(long, conflicts_with = "stub")]
+    check: bool,
+    /// Keep running and generate again whenever specs, files they reference, docs.html,
+    /// templates or configuration change
+    #[arg(long, conflicts_with_all = ["check", "stub"])]
+    watch: bool,
+    /// Print `todo!()` implementation of the api service for the given type
+    /// instead of generating the api
+    #[arg(long, value_name = "TYPE")]
+    stub: Option<String>,
+    /// Only print stubs of methods missing from the given implementation file
+    #[arg(long, value_name = "FILE", requires = "stub")]
+    missing_in: Option<PathBuf>,
 }
 
-fn is_openapi_spec(path: &Path) -> bool {
-    let Some(extension) = path.extension() else {
-        return false;
-    };
-    let Some(stem) = path.file_stem() else {
-        return false;
-    };
-    return extension == "yaml" && stem.to_string_lossy().contains("openapi");
+impl Args {
+    /// With `--spec`, the only positional argument which is not a directory is the output
+    /// path, as in `--spec openapi.yaml src/api.rs`
+    fn shift_out_path(&mut self) {
+        let shifted = !self.spec.is_empty()
+            && self.out_path.is_none()
+            && self.spec_dir.as_ref().is_some_and(|x| !x.is_dir());
+
+        if shifted {
+            self.out_path = self.spec_dir.take();
+        }
+    }
+
+    fn spec_dir(&self) -> Result<&Path> {
+        match &self.spec_dir {
+            Some(value) => Ok(value),
+            None => bail!("Spec directory is given neither by the arguments nor by configuration"),
+        }
+    }
+
+    fn out_path(&self) -> Result<&Path> {
+        match &self.out_path {
+            Some(value) => Ok(value),
+            None => bail!("Output path is given neither by the arguments nor by configuration"),
+        }
+    }
+
+    /// Take options not given by the arguments from the configuration,
+    /// flags given as `--mock=false` override the configuration as well
+    fn merge(&mut self, config: config::Config) -> Result<()> {
+        self.spec_dir = self.spec_dir.take().or(config.spec_dir);
+        self.out_path = self.out_path.take().or(config.out_path);
+        self.client = self.client.take().or(config.client);
+        self.mock = self.mock.or(config.mock);
+        self.document_errors = self.document_errors.or(config.document_errors);
+        self.metrics = self.metrics.or(config.metrics);
+        self.tracing = self.tracing.or(config.tracing);
+        self.split_by_tag = self.split_by_tag.or(config.split_by_tag);
+        self.yes = self.yes.or(config.yes);
+        self.title = self.title.take().or(config.title);
+        self.no_docs = self.no_docs.or(config.no_docs);
+        self.templates = self.templates.take().or(config.templates);
+
+        if self.spec.is_empty() {
+            self.spec = config.spec;
+        }
+
+        if self.exclude.is_empty() {
+            self.exclude = config.exclude;
+        }
+
+        if self.extractor.is_empty() {
+            for extractor in &config.extractor {
+                self.extractor.push(extractor.parse()?);
+            }
+        }
+
+        if self.derive.is_empty() {
+            self.derive = config.derive;
+        }
+
+        // Formats are mapped one by one, so the arguments override only the formats they map
+        for (format, type_) in config.type_map {
+            if !self.type_map.iter().any(|(x, _)| x == &format) {
+                self.type_map.push((format, type_));
+            }
+        }
+
+        if self.module_path.is_none() {
+            if let Some(path) = &config.module_path {
+                self.module_path = Some(generator::parse_module_path(path)?);
+            }
+        }
+
+        Ok(())
+    }
 }
 
-fn is_doc_page(path: &Path) -> bool {
-    let Some(filename) = path.file_name() else {
-        return false;
+/// Spec files given by `--spec` or found in the spec directory, except for the excluded ones
+fn select_specs(args: &Args) -> Result<Vec<PathBuf>> {
+    let spec_dir = match args.spec.is_empty() {
+        true => Some(args.spec_dir()?),
+        false => None,
     };
-    filename.to_ascii_lowercase() == "docs.html"
+
+    specs::select_specs(spec_dir, &args.spec, &args.exclude)
 }
 
 fn prompt_user(message: &str) -> Result<bool> {
@@ -83,65 +229,156 @@ fn get_user_input(message: &str, default: &str) -> Result<String> {
     }
 }
 
-#[derive(Serialize)]
-struct DocsRenderContext {
-    title: String,
+/// What to do when the spec directory has no docs.html
+struct MissingDocs {
+    /// Create it without asking
+    create: bool,
+    /// Embed the docs page into the generated code instead of creating it
+    embed: bool,
+    /// Application name shown on the docs page, asked when not given
+    title: Option<String>,
+    /// The user can be asked
+    interactive: bool,
 }
 
-static DOCS_HTML: &str = include_str!("generator/static/docs.html");
+/// Way of getting the docs page missing in the spec directory
+#[derive(Debug, PartialEq)]
+enum DocsAction {
+    Embed,
+    Create,
+    /// Ask the user whether to create it
+    Prompt,
+    /// Nobody can be asked
+    Fail,
+}
+
+impl MissingDocs {
+    /// Checking never creates the docs page, and the user is asked only from a terminal
+    /// when not watching, as the prompt would block the generation on every change
+    fn new(args: &Args, terminal: bool) -> Self {
+        Self {
+            create: args.yes.unwrap_or_default() && !args.check,
+            embed: args.no_docs.unwrap_or_default(),
+            title: args.title.clone(),
+            interactive: !args.check && !args.watch && terminal,
+        }
+    }
+
+    fn action(&self) -> DocsAction {
+        if self.embed {
+            DocsAction::Embed
+        } else if self.create {
+            DocsAction::Create
+        } else if self.interactive {
+            DocsAction::Prompt
+        } else {
+            DocsAction::Fail
+        }
+    }
+}
 
 fn create_docs_file(target_dir: &Path, title: &str) -> Result<PathBuf> {
     let mut target_file = target_dir.to_path_buf();
     target_file.push("docs.html");
 
-    let mut tera = Tera::default();
+    let rendered = generator::generate_docs(title).context("Could not render docs.html")?;
+
+    std::fs::write(target_file.clone(), rendered).context("Could not write docs.html")?;
+
+    Ok(target_file)
+}
 
-    let ctx = tera::Context::from_serialize(DocsRenderContext {
-        title: title.to_string(),
-    })?;
+/// Print unified diff of the file with its generated content, returns whether they differ
+fn check_file(path: &Path, generated: &str) -> Result<bool> {
+    let current = match read_to_string(path) {
+        Ok(value) => value,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Could not read {}", path.to_string_lossy()))
+        }
+    };
 
-    tera.add_raw_template("docs.html", DOCS_HTML)?;
+    if current == generated {
+        return Ok(false);
+    }
 
-    let rendered = tera
-        .render("docs.html", &ctx)
-        .context("Could not render docs.html")?;
+    let name = path.to_string_lossy();
+    let diff = TextDiff::from_lines(current.as_str(), generated);
 
-    std::fs::write(target_file.clone(), rendered).context("Could not write docs.html")?;
+    print!("{}", diff.unified_diff().header(&name, &name));
 
-    Ok(target_file)
+    Ok(true)
 }
 
-fn scan_dir(target_file: &Path, dir: &Path) -> Result<(String, Vec<generator::OpenapiWithMeta>)> {
+/// Finds specs and docs page in the directory, relative to the target file.
+/// Without the spec directory, the docs page is looked up next to the first spec and embedded
+/// when it is not there. Docs path is `None` when the docs page has to be embedded into the
+/// generated code
+fn scan_dir(
+    target_file: &Path,
+    spec_dir: Option<&Path>,
+    specs: &[PathBuf],
+    missing_docs: &MissingDocs,
+) -> Result<(Option<String>, Vec<generator::OpenapiWithMeta>)> {
+    let (docs_dir, action) = match spec_dir {
+        Some(value) => (value.to_path_buf(), missing_docs.action()),
+        None => {
+            let first = specs.first().context("No openapi specs were selected")?;
+            let dir = std::path::absolute(first)?
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+
+            (dir, DocsAction::Embed)
+        }
+    };
+    let dir = docs_dir.as_path();
+
     let paths = read_dir(dir)?;
     let mut openapi_files = Vec::new();
+    let mut versions = HashMap::new();
 
     let mut doc_path: Option<PathBuf> = None;
 
-    let mut target_dir = target_file.to_owned();
+    let mut target_dir = std::path::absolute(target_file)?;
     target_dir.pop();
 
+    for spec in specs {
+        let (content, version) = specs::read_spec(spec, &mut versions)?;
+
+        eprintln!("Using {} as v{version}", spec.to_string_lossy());
+
+        let path = std::path::absolute(spec)?;
+
+        let Some(path_rel) = pathdiff::diff_paths(&path, &target_dir) else {
+            bail!(
+                "Could not express path {} relative to {}",
+                path.to_string_lossy(),
+                target_dir.to_string_lossy()
+            )
+        };
+
+        let Some(path_rel) = path_rel.to_str() else {
+            bail!(
+                "Cannot represent relative {} path as string",
+                path_rel.to_string_lossy()
+            )
+        };
+
+        openapi_files.push(generator::OpenapiWithMeta {
+            content,
+            path: path_rel.to_string(),
+        })
+    }
+
     for path in paths {
         let path = path?;
         let path = if path.file_type()?.is_file() {
-            path.path()
+            std::path::absolute(path.path())?
         } else {
             continue;
         };
 
-        if is_openapi_spec(&path) {
-            let Some(path_rel) = pathdiff::diff_paths(&path, &target_dir) else {
-                bail!(format!("Could not express path {} relative to {}", path.to_string_lossy(), 
-                target_dir.to_string_lossy()))
-            };
-
-            let Some(path_rel) = path_rel.to_str() else {
-                bail!(format!("Cannot represent relative {} path as string", path_rel.to_string_lossy())) 
-            };
-            openapi_files.push(generator::OpenapiWithMeta {
-                content: read_to_string(&path).context("Could not read file")?,
-                path: path_rel.to_string(),
-            })
-        }
         if is_doc_page(&path) {
             if let Some(doc_path) = doc_path {
                 bail!(
@@ -156,19 +393,35 @@ fn scan_dir(target_file: &Path, dir: &Path) -> Result<(String, Vec<generator::Op
 
     let doc_path = match doc_path {
         Some(value) => value,
-        None => {
-            let user_agrees = prompt_user("Could not find docs.html. Create?").context(
-                "Could not find doc.html, and could not get user permission to create it",
-            )?;
-            if !user_agrees {
-                bail!("Could not find docs.html and User denied creation of it");
-            };
-            let title = get_user_input("Please, enter application name", "App")
-                .context("Could not get application name from user")?;
-            create_docs_file(dir, &title)?
-        }
+        None => match action {
+            DocsAction::Embed => return Ok((None, openapi_files)),
+            DocsAction::Create => {
+                let title = missing_docs.title.as_deref().unwrap_or(DEFAULT_TITLE);
+                create_docs_file(dir, title)?
+            }
+            DocsAction::Fail => bail!(
+                "Could not find docs.html in {}. Pass --yes to create it, or --no-docs to embed the docs page into the generated code",
+                dir.to_string_lossy()
+            ),
+            DocsAction::Prompt => {
+                let user_agrees = prompt_user("Could not find docs.html. Create?").context(
+                    "Could not find doc.html, and could not get user permission to create it",
+                )?;
+                if !user_agrees {
+                    bail!("Could not find docs.html and User denied creation of it");
+                };
+                let title = match &missing_docs.title {
+                    Some(value) => value.clone(),
+                    None => get_user_input("Please, enter application name", DEFAULT_TITLE)
+                        .context("Could not get application name from user")?,
+                };
+                create_docs_file(dir, &title)?
+            }
+        },
     };
 
+    let doc_path = std::path::absolute(doc_path)?;
+
     let Some(doc_path) = pathdiff::diff_paths(&doc_path, &target_dir) else {
         bail!(format!("Could not express path {} relative to {}", doc_path.to_string_lossy(), 
         target_dir.to_string_lossy()))
@@ -178,7 +431,312 @@ fn scan_dir(target_file: &Path, dir: &Path) -> Result<(String, Vec<generator::Op
         bail!("Could not represend doc path {} as string", doc_path.to_string_lossy())
     };
 
-    Ok((doc_path.to_string(), openapi_files))
+    Ok((Some(doc_path.to_string()), openapi_files))
+}
+
+/// Generate the files, or check them or print the stub. Returns paths of the files written
+fn generate(args: &Args, module_path: &Option<String>) -> Result<Vec<PathBuf>> {
+    let missing_docs = MissingDocs::new(args, std::io::stdin().is_terminal());
+
+    let specs = select_specs(args)?;
+
+    let (docs_file, openapi_specs) = scan_dir(
+        args.out_path()?,
+        args.spec_dir.as_deref(),
+        &specs,
+        &missing_docs,
+    )?;
+
+    let docs_html = match &docs_file {
+        Some(_) => None,
+        None => {
+            let title = args.title.as_deref().unwrap_or(DEFAULT_TITLE);
+            Some(generator::generate_docs(title).context("Could not render docs page")?)
+        }
+    };
+
+    let options = generator::ApiOptions {
+        mock: args.mock.unwrap_or_default(),
+        document_errors: args.document_errors.unwrap_or_default(),
+        metrics: args.metrics.unwrap_or_default(),
+        tracing: args.tracing.unwrap_or_default(),
+        extractors: args.extractor.clone(),
+        split_by_tag: args.split_by_tag.unwrap_or_default(),
+        docs_html,
+        module_path: module_path.clone(),
+        included: false,
+        derives: args.derive.clone(),
+        type_map: args.type_map.iter().cloned().collect(),
+        templates: args.templates.clone(),
+    };
+
+    if let Some(server) = &args.stub {
+        let implementation = match &args.missing_in {
+            Some(path) => Some(read_to_string(path).with_context(|| {
+                format!("Could not read implementation {}", path.to_string_lossy())
+            })?),
+            None => None,
+        };
+
+        let stub =
+            generator::generate_stub(&openapi_specs, server, implementation.as_deref(), &options)?;
+
+        println!("{stub}");
+
+        return Ok(Vec::new());
+    }
+
+    let (_, generated) = generator::generate_api(
+        docs_file.as_deref().unwrap_or("docs.html"),
+        &openapi_specs,
+        &options,
+    )?;
+    let mut outputs = vec![(args.out_path()?.to_path_buf(), generated)];
+
+    if let Some(client_path) = &args.client {
+        let client = generator::generate_client(&openapi_specs, &options)?;
+        outputs.push((client_path.clone(), client));
+    }
+
+    if args.check {
+        let mut stale = Vec::new();
+
+        for (path, generated) in &outputs {
+            if check_file(path, generated)? {
+                stale.push(path.to_string_lossy().to_string());
+            }
+        }
+
+        if !stale.is_empty() {
+            bail!("Generated code is not up to date: {}", stale.join(", "));
+        }
+
+        return Ok(Vec::new());
+    }
+
+    let mut written = Vec::new();
+
+    for (path, generated) in outputs {
+        // Files that did not change are not touched, so that they are not rebuilt
+        if read_to_string(&path).ok().as_deref() == Some(generated.as_str()) {
+            continue;
+        }
+
+        std::fs::write(&path, generated)
+            .with_context(|| format!("Could not write into {}", path.to_string_lossy()))?;
+
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Delay after the last change of the spec directory before generating again
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Modification time of the watched file, `None` when it does not exist
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|x| x.modified()).ok()
+}
+
+/// Changes of the file may change the generated code
+fn is_watched(path: &Path) -> bool {
+    let generated_from = path
+        .extension()
+        .is_some_and(|x| x == "yaml" || x == "yml" || x == "json" || x == "tera");
+
+    let config = path
+        .file_name()
+        .is_some_and(|x| x == config::CONFIG_FILE || x == "Cargo.toml");
+
+    generated_from || config || is_doc_page(path)
+}
+
+/// Directories of the files read by the generation: the specs along with the files
+/// they reference, templates and the configuration files
+fn watched_dirs(args: &Args) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    if let Some(path) = config::Config::manifest_path(&env::current_dir()?) {
+        files.push(path);
+    }
+
+    if let Some(dir) = &args.spec_dir {
+        files.push(dir.join(config::CONFIG_FILE));
+    }
+
+    // Specs given by `--spec`, the files they reference and templates may be
+    // outside of the spec directory
+    for spec in select_specs(args).unwrap_or_default() {
+        files.extend(specs::referenced_files(&spec));
+        files.push(spec);
+    }
+
+    if let Some(dir) = &args.templates {
+        files.push(dir.join("api.tera"));
+    }
+
+    let mut dirs = Vec::new();
+
+    for file in files {
+        if let Some(dir) = std::path::absolute(file)?.parent() {
+            if !dirs.iter().any(|x| x == dir) {
+                dirs.push(dir.to_path_buf());
+            }
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Generate again on every change of the specs or of the configuration,
+/// reporting errors instead of exiting
+fn watch(cli: &Args) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+
+    let mut debouncer =
+        new_debouncer(WATCH_DEBOUNCE, sender).context("Could not create watcher")?;
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    // Reading the specs is reported as well, so changes are told by modification times
+    let mut modified_at: HashMap<PathBuf, Option<SystemTime>> = HashMap::new();
+
+    loop {
+        // Configuration is read again, as it may have changed as well
+        let args = match configure(cli.clone()) {
+            Ok((args, module_path)) => {
+                match generate(&args, &module_path) {
+                    Ok(written) if written.is_empty() => println!("Generated code is up to date"),
+                    Ok(written) => {
+                        for path in written {
+                            println!("Generated {}", path.to_string_lossy());
+                        }
+                    }
+                    Err(error) => eprintln!("Error: {error:?}"),
+                }
+                args
+            }
+            Err(error) => {
+                eprintln!("Error: {error:?}");
+                cli.clone()
+            }
+        };
+
+        let watched = match watched_dirs(&args) {
+            Ok(value) => value,
+            Err(error) => {
+                eprintln!("Error: {error:?}");
+                Vec::new()
+            }
+        };
+
+        // Specs and their references may have changed, so the new directories are added
+        for dir in watched {
+            if dirs.contains(&dir) || !dir.is_dir() {
+                continue;
+            }
+
+            let watching = debouncer
+                .watcher()
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Could not watch {}", dir.to_string_lossy()));
+
+            if let Err(error) = watching {
+                eprintln!("Error: {error:?}");
+                continue;
+            }
+
+            let entries = match read_dir(&dir) {
+                Ok(value) => value,
+                Err(error) => {
+                    eprintln!("Error: Could not read {}: {error}", dir.to_string_lossy());
+                    continue;
+                }
+            };
+
+            for entry in entries {
+                match entry {
+                    Ok(entry) => {
+                        let path = entry.path();
+                        modified_at.insert(path.clone(), modified(&path));
+                    }
+                    Err(error) => {
+                        eprintln!("Error: Could not read {}: {error}", dir.to_string_lossy())
+                    }
+                }
+            }
+
+            dirs.push(dir);
+        }
+
+        for dir in &dirs {
+            println!("Watching {} for changes", dir.to_string_lossy());
+        }
+
+        loop {
+            match receiver.recv().context("Watcher stopped")? {
+                Ok(events) => {
+                    let mut changed = false;
+
+                    for event in events {
+                        if !is_watched(&event.path) {
+                            continue;
+                        }
+
+                        let time = modified(&event.path);
+
+                        if modified_at.insert(event.path, time) != Some(time) {
+                            changed = true;
+                        }
+                    }
+
+                    if changed {
+                        break;
+                    }
+                }
+                Err(error) => eprintln!("Watch error: {error:?}"),
+            }
+        }
+    }
+}
+
+/// Complete the arguments with the configuration files and find module path of the output
+fn configure(mut args: Args) -> Result<(Args, Option<String>)> {
+    args.shift_out_path();
+
+    // Configuration next to the specs takes precedence over the one in Cargo.toml
+    let manifest_config =
+        config::Config::from_manifest(&env::current_dir().context("Could not get current dir")?)?
+            .unwrap_or_default();
+
+    let spec_dir = args.spec_dir.clone().or(manifest_config.spec_dir.clone());
+
+    if let Some(spec_dir) = spec_dir {
+        if let Some(config) = config::Config::from_dir(&spec_dir)? {
+            args.merge(config)?;
+        }
+    }
+
+    args.merge(manifest_config)?;
+
+    let module_path = match &args.module_path {
+        Some(value) => Some(value.clone()),
+        None => {
+            let derived = generator::module_path(args.out_path()?);
+
+            if derived.is_none() {
+                eprintln!(
+                    "Could not derive module path of {}, assuming server::api. Pass --module-path to change it",
+                    args.out_path()?.to_string_lossy()
+                );
+            }
+
+            derived
+        }
+    };
+
+    Ok((args, module_path))
 }
 
 fn main() -> Result<()> {
@@ -193,12 +751,144 @@ fn main() -> Result<()> {
 
     let args = Args::parse_from(args);
 
-    let (docs_file, openapi_specs) = scan_dir(&args.out_path, &args.spec_dir)?;
+    if let Some(dir) = &args.dump_templates {
+        for path in generator::dump_templates(dir)? {
+            println!("Written {}", path.to_string_lossy());
+        }
+
+        return Ok(());
+    }
 
-    let (_, generated) = generator::generate_api(&docs_file, &openapi_specs)?;
+    if args.watch {
+        return watch(&args);
+    }
+
+    let (args, module_path) = configure(args)?;
 
-    std::fs::write(args.out_path.clone(), generated)
-        .with_context(|| format!("Could not result into {}", args.out_path.to_string_lossy()))?;
+    generate(&args, &module_path)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing_docs(args: &[&str], terminal: bool) -> MissingDocs {
+        let args =
+            Args::parse_from(std::iter::once("cargo-actix-openapi").chain(args.iter().copied()));
+
+        MissingDocs::new(&args, terminal)
+    }
+
+    #[test]
+    fn test_docs_action() {
+        assert_eq!(missing_docs(&[], true).action(), DocsAction::Prompt);
+        assert_eq!(missing_docs(&[], false).action(), DocsAction::Fail);
+        assert_eq!(missing_docs(&["--yes"], false).action(), DocsAction::Create);
+        assert_eq!(
+            missing_docs(&["--no-docs"], true).action(),
+            DocsAction::Embed
+        );
+        assert_eq!(
+            missing_docs(&["--no-docs", "--check"], true).action(),
+            DocsAction::Embed
+        );
+
+        // Watching never blocks on the prompt
+        assert_eq!(missing_docs(&["--watch"], true).action(), DocsAction::Fail);
+        assert_eq!(
+            missing_docs(&["--watch", "--yes"], true).action(),
+            DocsAction::Create
+        );
+
+        // Checking neither asks nor creates the docs page
+        assert_eq!(missing_docs(&["--check"], true).action(), DocsAction::Fail);
+        assert_eq!(
+            missing_docs(&["--check", "--yes"], true).action(),
+            DocsAction::Fail
+        );
+    }
+
+    fn merged(args: &[&str], config: &str) -> Args {
+        let mut args =
+            Args::parse_from(std::iter::once("cargo-actix-openapi").chain(args.iter().copied()));
+
+        args.merge(toml::from_str(config).unwrap()).unwrap();
+        args
+    }
+
+    #[test]
+    fn test_merge_flags() {
+        let config = "mock = true\nno-docs = true";
+
+        assert_eq!(merged(&[], config).mock, Some(true));
+        assert_eq!(merged(&["--mock=false"], config).mock, Some(false));
+        assert_eq!(merged(&["--no-docs=false"], config).no_docs, Some(false));
+        assert_eq!(merged(&["--tracing"], config).tracing, Some(true));
+        assert_eq!(merged(&[], config).tracing, None);
+        assert_eq!(merged(&["-y"], config).yes, Some(true));
+    }
+
+    #[test]
+    fn test_merge_type_map() {
+        let config = "[type-map]\nuuid = \"uuid::Uuid\"\ndate = \"chrono::NaiveDate\"";
+
+        let mut type_map = merged(&["--type-map", "uuid=String"], config).type_map;
+        type_map.sort();
+
+        assert_eq!(
+            type_map,
+            vec![
+                ("date".to_string(), "chrono::NaiveDate".to_string()),
+                ("uuid".to_string(), "String".to_string()),
+            ]
+        );
+    }
+
+    /// Scans the directory with a spec and without docs page
+    fn scan(missing_docs: &MissingDocs) -> (tempfile::TempDir, Result<Option<String>>) {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = dir.path().join("openapi.yaml");
+
+        std::fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/openapi/helloworld.yaml"),
+            &spec,
+        )
+        .unwrap();
+
+        let result = scan_dir(
+            &dir.path().join("api.rs"),
+            Some(dir.path()),
+            &[spec],
+            missing_docs,
+        )
+        .map(|(docs, specs)| {
+            assert_eq!(specs.len(), 1);
+            docs
+        });
+
+        (dir, result)
+    }
+
+    #[test]
+    fn test_scan_dir_no_docs() {
+        let (dir, docs) = scan(&missing_docs(&["--no-docs"], true));
+
+        assert_eq!(docs.unwrap(), None);
+        assert!(!dir.path().join("docs.html").exists());
+    }
+
+    #[test]
+    fn test_scan_dir_missing_docs() {
+        let (dir, docs) = scan(&missing_docs(&["--yes"], false));
+
+        assert_eq!(docs.unwrap().as_deref(), Some("docs.html"));
+        assert!(dir.path().join("docs.html").exists());
+
+        let (dir, docs) = scan(&missing_docs(&["--check"], true));
+
+        assert!(docs.is_err());
+        assert!(!dir.path().join("docs.html").exists());
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::{bail, Result};
 use indexmap::IndexMap;
 use openapiv3::{
     Components, MediaType, Parameter, ParameterData, ParameterSchemaOrContent, PathItem, PathStyle,
-    QueryStyle, ReferenceOr, RequestBody, Response, Schema,
+    QueryStyle, ReferenceOr, RequestBody, Response, Schema, SecurityScheme,
 };
 
 pub enum ParametersType {
@@ -207,6 +207,22 @@ impl Dereferencing<RequestBody> for RequestBody {
     }
 }
 
+impl Dereferencing<SecurityScheme> for SecurityScheme {
+    fn dereference<'a>(
+        components: &'a Components,
+        namespace: &str,
+        name: &str,
+    ) -> Result<&'a SecurityScheme> {
+        verify_namespace("securitySchemes", namespace)?;
+
+        let Some(value) = components.security_schemes.get(name) else {
+            bail!("Reference not found")
+        };
+
+        get_inner_reference(value)
+    }
+}
+
 fn deref_any<'a, T>(components: &'a Option<Components>, obj_ref: &str) -> Result<&'a T>
 where
     T: Dereferencing<T>,
@@ -0,0 +1,259 @@
+//! Spec files and docs page of the spec directory, found the same way by the command line
+//! and by the builder
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{canonicalize, read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::generator;
+
+/// Application name of the docs page when it is not given
+pub const DEFAULT_TITLE: &str = "App";
+
+pub fn is_openapi_spec(path: &Path) -> bool {
+    let Some(extension) = path.extension() else {
+        return false;
+    };
+    let Some(stem) = path.file_stem() else {
+        return false;
+    };
+    extension == "yaml" && stem.to_string_lossy().contains("openapi")
+}
+
+pub fn is_doc_page(path: &Path) -> bool {
+    let Some(filename) = path.file_name() else {
+        return false;
+    };
+    filename.eq_ignore_ascii_case("docs.html")
+}
+
+fn is_pattern(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+/// Pattern without `/` matches the file name, otherwise the absolute path
+pub fn is_excluded(pattern: &str, path: &Path) -> Result<bool> {
+    if !pattern.contains('/') {
+        let pattern = glob::Pattern::new(pattern)?;
+        return Ok(path
+            .file_name()
+            .is_some_and(|x| pattern.matches(&x.to_string_lossy())));
+    }
+
+    let pattern = std::path::absolute(pattern)?;
+    let pattern = glob::Pattern::new(&pattern.to_string_lossy())?;
+
+    Ok(pattern.matches_path(&std::path::absolute(path)?))
+}
+
+/// `openapi*.yaml` files of the spec directory when given, and the spec files or glob patterns,
+/// except for the excluded ones
+pub fn select_specs(
+    spec_dir: Option<&Path>,
+    specs: &[String],
+    exclude: &[String],
+) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+
+    if let Some(dir) = spec_dir {
+        for entry in read_dir(dir).with_context(|| format!("Could not read {}", dir.display()))? {
+            let path = entry?.path();
+
+            if path.is_file() && is_openapi_spec(&path) {
+                found.push(path);
+            }
+        }
+    }
+
+    for spec in specs {
+        if !is_pattern(spec) {
+            let path = PathBuf::from(spec);
+
+            if !path.is_file() {
+                bail!("Spec {spec} does not exist");
+            }
+
+            found.push(path);
+            continue;
+        }
+
+        let mut matched = false;
+
+        for path in glob::glob(spec).with_context(|| format!("Invalid pattern {spec}"))? {
+            let path = path?;
+
+            if path.is_file() {
+                found.push(path);
+                matched = true;
+            }
+        }
+
+        if !matched {
+            bail!("Pattern {spec} does not match any file");
+        }
+    }
+
+    let mut selected = Vec::new();
+    // Same spec may be given by different paths, like `./openapi.yaml` and by the spec directory
+    let mut canonical = HashSet::new();
+
+    for path in found {
+        let mut excluded = false;
+
+        for pattern in exclude {
+            excluded |= is_excluded(pattern, &path)
+                .with_context(|| format!("Invalid exclude pattern {pattern}"))?;
+        }
+
+        let canonical_path = canonicalize(&path)
+            .with_context(|| format!("Could not find {}", path.to_string_lossy()))?;
+
+        if !excluded && canonical.insert(canonical_path) {
+            selected.push(path);
+        }
+    }
+
+    selected.sort();
+
+    if selected.is_empty() {
+        bail!("No openapi specs were selected");
+    }
+
+    Ok(selected)
+}
+
+/// Collects files of the `$ref`s like `../common/schemas.yaml#/Pet` in the yaml document
+fn collect_refs(value: &serde_yaml::Value, dir: &Path, files: &mut Vec<PathBuf>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                if let (Some("$ref"), Some(reference)) = (key.as_str(), value.as_str()) {
+                    let file = reference.split('#').next().unwrap_or_default();
+
+                    if !file.is_empty() && !file.contains("://") {
+                        files.push(dir.join(file));
+                    }
+                }
+
+                collect_refs(value, dir, files);
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                collect_refs(item, dir, files);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Files referenced by the spec with `$ref`, directly or through other referenced files.
+/// Files that can not be read are skipped, the generation reports them
+pub fn referenced_files(spec: &Path) -> Vec<PathBuf> {
+    let spec = canonicalize(spec).unwrap_or(spec.to_path_buf());
+    let mut pending = vec![spec.clone()];
+    let mut found: Vec<PathBuf> = Vec::new();
+
+    while let Some(path) = pending.pop() {
+        let Ok(content) = read_to_string(&path) else {
+            continue;
+        };
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&content) else {
+            continue;
+        };
+
+        let mut files = Vec::new();
+        collect_refs(&value, path.parent().unwrap_or(Path::new("")), &mut files);
+
+        for file in files {
+            let file = canonicalize(&file).unwrap_or(file);
+
+            if file != spec && !found.contains(&file) {
+                found.push(file.clone());
+                pending.push(file);
+            }
+        }
+    }
+
+    found
+}
+
+/// Reads the spec and its major version, failing when one of the specs read before
+/// has the same major version
+pub fn read_spec(path: &Path, versions: &mut HashMap<usize, PathBuf>) -> Result<(String, usize)> {
+    let content = read_to_string(path)
+        .with_context(|| format!("Could not read {}", path.to_string_lossy()))?;
+
+    let version = generator::spec_version(&content)
+        .with_context(|| format!("Could not get version of {}", path.to_string_lossy()))?;
+
+    if let Some(other) = versions.insert(version, path.to_path_buf()) {
+        bail!(
+            "Specs {} and {} have the same major version {version}",
+            other.to_string_lossy(),
+            path.to_string_lossy()
+        );
+    }
+
+    Ok((content, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_specs_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = dir.path().join("openapi.yaml");
+
+        std::fs::write(&spec, "").unwrap();
+
+        let given = [
+            spec.to_string_lossy().to_string(),
+            dir.path()
+                .join(".")
+                .join("openapi.yaml")
+                .to_string_lossy()
+                .to_string(),
+            dir.path().join("*.yaml").to_string_lossy().to_string(),
+        ];
+
+        let selected = select_specs(Some(dir.path()), &given, &[]).unwrap();
+
+        assert_eq!(selected, vec![spec]);
+    }
+
+    #[test]
+    fn test_referenced_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path().join("common");
+        let spec = dir.path().join("api").join("openapi.yaml");
+
+        std::fs::create_dir_all(&common).unwrap();
+        std::fs::create_dir_all(spec.parent().unwrap()).unwrap();
+
+        std::fs::write(
+            &spec,
+            "paths:\n  /pets:\n    $ref: '../common/paths.yaml#/pets'\nlocal:\n  $ref: '#/components/schemas/Pet'\n",
+        )
+        .unwrap();
+        std::fs::write(
+            common.join("paths.yaml"),
+            "pets:\n  - $ref: 'schemas.yaml#/Pet'\n  - $ref: 'https://example.com/pet.yaml'\n",
+        )
+        .unwrap();
+        std::fs::write(common.join("schemas.yaml"), "Pet: {}\n").unwrap();
+
+        let common = canonicalize(&common).unwrap();
+
+        assert_eq!(
+            referenced_files(&spec),
+            vec![common.join("paths.yaml"), common.join("schemas.yaml")]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs::{copy, read_to_string, write},
+    path::Path,
+    process::{Command, Output},
+};
+
+use tempfile::TempDir;
+
+/// Spec directory with the helloworld spec and its docs page
+fn spec_dir() -> TempDir {
+    let dir = tempfile::tempdir().expect("Could not create temp dir");
+    let spec = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/openapi/helloworld.yaml");
+
+    copy(spec, dir.path().join("openapi.yaml")).expect("Could not copy spec");
+    write(dir.path().join("docs.html"), "").expect("Could not write docs page");
+
+    dir
+}
+
+/// Run the binary inside of the directory, generating `api.rs` from its specs
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-actix-openapi"))
+        .current_dir(dir)
+        .args([".", "api.rs", "--module-path", "api"])
+        .args(args)
+        .output()
+        .expect("Could not run the generator")
+}
+
+#[test]
+fn test_check_up_to_date() {
+    let dir = spec_dir();
+
+    assert!(run(dir.path(), &[]).status.success());
+
+    let output = run(dir.path(), &["--check"]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_check_stale() {
+    let dir = spec_dir();
+    let target = dir.path().join("api.rs");
+
+    assert!(run(dir.path(), &[]).status.success());
+
+    let generated = read_to_string(&target).unwrap();
+    write(&target, generated.replace("hello", "goodbye")).unwrap();
+
+    let output = run(dir.path(), &["--check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout
+        .lines()
+        .any(|x| x.starts_with('-') && x.contains("goodbye")));
+    assert!(stderr.contains("Generated code is not up to date"));
+
+    // Checking never writes the files
+    assert_ne!(read_to_string(&target).unwrap(), generated);
+}
+
+#[test]
+fn test_check_missing() {
+    let dir = spec_dir();
+
+    let output = run(dir.path(), &["--check"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!dir.path().join("api.rs").exists());
+}
+
+#[test]
+fn test_derive() {
+    let dir = spec_dir();
+
+    let output = run(dir.path(), &["--derive", "Hash", "--client", "client.rs"]);
+    assert!(output.status.success());
+
+    for file in ["api.rs", "client.rs"] {
+        let generated = read_to_string(dir.path().join(file)).unwrap();
+        assert!(generated.contains("PartialEq, Eq, Hash)]"), "{file}");
+    }
+}
+
+#[test]
+fn test_derive_config() {
+    let dir = spec_dir();
+
+    write(
+        dir.path().join("actix-openapi.toml"),
+        "derive = [\"Hash\", \"PartialOrd\"]\n",
+    )
+    .unwrap();
+
+    assert!(run(dir.path(), &[]).status.success());
+
+    let generated = read_to_string(dir.path().join("api.rs")).unwrap();
+    assert!(generated.contains("PartialEq, Eq, Hash, PartialOrd)]"));
+
+    // Derives of the arguments replace the configured ones
+    assert!(run(dir.path(), &["--derive", "Ord"]).status.success());
+
+    let generated = read_to_string(dir.path().join("api.rs")).unwrap();
+    assert!(generated.contains("PartialEq, Eq, Ord)]"));
+    assert!(!generated.contains("PartialOrd"));
+}
+
+#[test]
+fn test_spec_without_dir() {
+    let dir = spec_dir();
+    let generate = || {
+        Command::new(env!("CARGO_BIN_EXE_cargo-actix-openapi"))
+            .current_dir(dir.path())
+            .args(["--spec", "openapi.yaml", "api.rs", "--module-path", "api"])
+            .output()
+            .expect("Could not run the generator")
+    };
+
+    // docs.html next to the spec is served
+    let output = generate();
+    assert!(output.status.success(), "{output:?}");
+
+    let generated = read_to_string(dir.path().join("api.rs")).unwrap();
+    assert!(generated.contains("include_str!(\"docs.html\")"));
+
+    // Without it, the default docs page is embedded instead of asking to create it
+    std::fs::remove_file(dir.path().join("docs.html")).unwrap();
+
+    let output = generate();
+    assert!(output.status.success(), "{output:?}");
+
+    let generated = read_to_string(dir.path().join("api.rs")).unwrap();
+    assert!(!generated.contains("include_str!(\"docs.html\")"));
+    assert!(!dir.path().join("docs.html").exists());
+}
@@ -0,0 +1,233 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API client auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use reqwest::StatusCode;
+
+// Defaults
+// -------------------------------
+
+// Enums
+// -------------------------------
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GreetUserPath {
+    /// The name of the user to greet.
+    pub user: String,
+}
+
+// Error with details
+// -------------------------------
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+/// Error of the api call
+#[derive(Debug)]
+pub enum ClientError<E> {
+    /// Server responded with one of the errors declared in the spec
+    Api(Detailed<E>),
+    /// Server responded with status or body not declared in the spec
+    UnexpectedResponse { status: StatusCode, body: String },
+    /// Request could not be built from the arguments
+    InvalidRequest(String),
+    /// Request could not be sent or response could not be read
+    Transport(reqwest::Error),
+}
+
+impl<E: Display> Display for ClientError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Api(error) => write!(f, "{error}"),
+            Self::UnexpectedResponse { status, body } => {
+                write!(f, "Unexpected response {status}: {body}")
+            }
+            Self::InvalidRequest(reason) => write!(f, "Invalid request: {reason}"),
+            Self::Transport(error) => write!(f, "Transport error: {error}"),
+        }
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for ClientError<E> {}
+
+/// Api error that can be decoded from the server response
+pub trait DecodeError: Sized {
+    fn decode(status: StatusCode, body: &str) -> Option<Detailed<Self>>;
+}
+
+impl DecodeError for std::convert::Infallible {
+    fn decode(_status: StatusCode, _body: &str) -> Option<Detailed<Self>> {
+        None
+    }
+}
+
+/// Find variant by status code and problem title the server renders `Detailed` errors with
+fn decode_variant<E: Clone>(
+    status: StatusCode,
+    body: &str,
+    variants: &[(StatusCode, &str, E)],
+) -> Option<Detailed<E>> {
+    let candidates: Vec<&(StatusCode, &str, E)> = variants
+        .iter()
+        .filter(|(code, _, _)| *code == status)
+        .collect();
+    let Ok(problem) = serde_json::from_str::<ProblemDetails>(body) else {
+        return match candidates.as_slice() {
+            [(_, _, error)] => {
+                Some(Detailed {
+                    error: error.clone(),
+                    details: body.to_string(),
+                    instance: None,
+                })
+            }
+            _ => None,
+        };
+    };
+    let (_, _, error) = match candidates.as_slice() {
+        [candidate] => candidate,
+        _ => candidates.iter().find(|(_, display, _)| *display == problem.title)?,
+    };
+    Some(Detailed {
+        error: error.clone(),
+        details: problem.detail,
+        instance: problem.instance,
+    })
+}
+
+// Error
+// -------------------------------
+
+// Api client
+// -------------------------------
+
+fn path_params(
+    path: &impl Serialize,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    match serde_json::to_value(path) {
+        Ok(serde_json::Value::Object(params)) => Ok(params),
+        Ok(_) => Err("Path parameters must serialize into object".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn send_raw<E: DecodeError>(
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, ClientError<E>> {
+    let response = request.send().await.map_err(ClientError::Transport)?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.map_err(ClientError::Transport)?;
+    match E::decode(status, &body) {
+        Some(error) => Err(ClientError::Api(error)),
+        None => {
+            Err(ClientError::UnexpectedResponse {
+                status,
+                body,
+            })
+        }
+    }
+}
+
+async fn send<T: DeserializeOwned, E: DecodeError>(
+    request: reqwest::RequestBuilder,
+) -> Result<T, ClientError<E>> {
+    let response = send_raw(request).await?;
+    response.json().await.map_err(ClientError::Transport)
+}
+
+/// Typed client of the api
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: reqwest::Url,
+    client: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: reqwest::Url) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+    /// Create api client that sends requests with pre-configured reqwest client
+    pub fn with_client(base_url: reqwest::Url, client: reqwest::Client) -> Self {
+        ApiClient { base_url, client }
+    }
+    fn url(
+        &self,
+        template: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<reqwest::Url, String> {
+        let mut url = self.base_url.clone();
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| "Base url can not be a base".to_string())?;
+            segments.pop_if_empty();
+            for segment in template.split('/').filter(|x| !x.is_empty()) {
+                let mut segment = segment.to_string();
+                for (name, value) in params {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    segment = segment.replace(&["{", name, "}"].concat(), &value);
+                }
+                segments.push(&segment);
+            }
+        }
+        Ok(url)
+    }
+    /// Returns a greeting to the user!
+    pub async fn greet_user(
+        &self,
+        path: &GreetUserPath,
+    ) -> Result<String, ClientError<std::convert::Infallible>> {
+        let params = path_params(path).map_err(ClientError::InvalidRequest)?;
+        let url = self
+            .url("/v1/hello/{user}", &params)
+            .map_err(ClientError::InvalidRequest)?;
+        #[allow(unused_mut)]
+        let mut request = self.client.get(url);
+        send(request).await
+    }
+}
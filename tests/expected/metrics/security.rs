@@ -0,0 +1,1352 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use actix_web::{
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+
+use actix_web::{
+    body::MessageBody, dev::{Service, ServiceRequest, ServiceResponse, Transform},
+};
+
+use async_trait::async_trait;
+
+// Defaults
+// -------------------------------
+
+// Enums
+// -------------------------------
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GetPetPath {
+    pub pet_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct StatusQuery {
+    pub token: Option<String>,
+}
+
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+// Validation
+// -------------------------------
+
+/// Value that is checked against constraints declared in the spec
+pub trait Validate {
+    /// Collect constraint violations of the value located at `path`
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>);
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        self.validate_into("", &mut errors);
+        ValidationErrors::check(errors)
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        if let Some(value) = self {
+            value.validate_into(path, errors)
+        }
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        for (index, value) in self.iter().enumerate() {
+            value.validate_into(&format!("{path}[{index}]"), errors)
+        }
+    }
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    match path.is_empty() {
+        true => field.to_string(),
+        false => format!("{path}.{field}"),
+    }
+}
+
+fn check_length(
+    path: &str,
+    value: &str,
+    min: Option<usize>,
+    max: Option<usize>,
+    errors: &mut Vec<FieldError>,
+) {
+    let length = value.chars().count();
+    if let Some(min) = min.filter(|min| length < *min) {
+        errors
+            .push(
+                FieldError::new(path, format!("must be at least {min} characters long")),
+            );
+    }
+    if let Some(max) = max.filter(|max| length > *max) {
+        errors
+            .push(
+                FieldError::new(path, format!("must be at most {max} characters long")),
+            );
+    }
+}
+
+fn check_range<T: PartialOrd + Display + Copy>(
+    path: &str,
+    value: T,
+    min: Option<(T, bool)>,
+    max: Option<(T, bool)>,
+    errors: &mut Vec<FieldError>,
+) {
+    if let Some((min, exclusive)) = min {
+        if value < min || (exclusive && value == min) {
+            let relation = match exclusive {
+                true => "greater than",
+                false => "greater than or equal to",
+            };
+            errors.push(FieldError::new(path, format!("must be {relation} {min}")));
+        }
+    }
+    if let Some((max, exclusive)) = max {
+        if value > max || (exclusive && value == max) {
+            let relation = match exclusive {
+                true => "less than",
+                false => "less than or equal to",
+            };
+            errors.push(FieldError::new(path, format!("must be {relation} {max}")));
+        }
+    }
+}
+
+fn check_multiple_of_int(
+    path: &str,
+    value: i64,
+    divisor: i64,
+    errors: &mut Vec<FieldError>,
+) {
+    if divisor != 0 && value % divisor != 0 {
+        errors.push(FieldError::new(path, format!("must be a multiple of {divisor}")));
+    }
+}
+
+fn check_multiple_of_float(
+    path: &str,
+    value: f64,
+    divisor: f64,
+    errors: &mut Vec<FieldError>,
+) {
+    let quotient = value / divisor;
+    if (quotient - quotient.round()).abs() > 1e-9 {
+        errors.push(FieldError::new(path, format!("must be a multiple of {divisor}")));
+    }
+}
+
+fn check_items<T: PartialEq>(
+    path: &str,
+    value: &[T],
+    min: Option<usize>,
+    max: Option<usize>,
+    unique: bool,
+    errors: &mut Vec<FieldError>,
+) {
+    if let Some(min) = min.filter(|min| value.len() < *min) {
+        errors.push(FieldError::new(path, format!("must contain at least {min} items")));
+    }
+    if let Some(max) = max.filter(|max| value.len() > *max) {
+        errors.push(FieldError::new(path, format!("must contain at most {max} items")));
+    }
+    if unique && (1..value.len()).any(|index| value[..index].contains(&value[index])) {
+        errors.push(FieldError::new(path, "must contain unique items"));
+    }
+}
+
+fn check_format(path: &str, value: &str, format: &str, errors: &mut Vec<FieldError>) {
+    let valid = match format {
+        "date" => is_date(value),
+        "date-time" => is_date_time(value),
+        "email" => is_email(value),
+        "uuid" => is_uuid(value),
+        "ipv4" => value.parse::<std::net::Ipv4Addr>().is_ok(),
+        "ipv6" => value.parse::<std::net::Ipv6Addr>().is_ok(),
+        "uri" => is_uri(value),
+        _ => true,
+    };
+    if !valid {
+        errors.push(FieldError::new(path, format!("must be a valid {format}")));
+    }
+}
+
+fn digits(value: &str) -> Option<u32> {
+    match !value.is_empty() && value.bytes().all(|x| x.is_ascii_digit()) {
+        true => value.parse().ok(),
+        false => None,
+    }
+}
+
+/// Full date as defined by RFC 3339, like `2023-01-31`
+fn is_date(value: &str) -> bool {
+    let (Some(_year), Some(month), Some(day)) = (
+        value.get(0..4).and_then(digits),
+        value.get(5..7).and_then(digits),
+        value.get(8..10).and_then(digits),
+    ) else {
+        return false;
+    };
+    value.len() == 10 && value.get(4..5) == Some("-") && value.get(7..8) == Some("-")
+        && (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+/// Full time as defined by RFC 3339, like `23:59:59.999+02:00`
+fn is_time(value: &str) -> bool {
+    let (Some(hour), Some(minute), Some(second)) = (
+        value.get(0..2).and_then(digits),
+        value.get(3..5).and_then(digits),
+        value.get(6..8).and_then(digits),
+    ) else {
+        return false;
+    };
+    if value.get(2..3) != Some(":") || value.get(5..6) != Some(":") {
+        return false;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+    let mut offset = &value[8..];
+    if let Some(fraction) = offset.strip_prefix('.') {
+        let length = fraction.bytes().take_while(|x| x.is_ascii_digit()).count();
+        if length == 0 {
+            return false;
+        }
+        offset = &fraction[length..];
+    }
+    match offset {
+        "Z" | "z" => true,
+        offset => {
+            offset.len() == 6 && matches!(offset.get(0..1), Some("+" | "-"))
+                && offset.get(1..3).and_then(digits).is_some_and(|x| x <= 23)
+                && offset.get(3..4) == Some(":")
+                && offset.get(4..6).and_then(digits).is_some_and(|x| x <= 59)
+        }
+    }
+}
+
+/// Date and time as defined by RFC 3339, like `2023-01-31T23:59:59Z`
+fn is_date_time(value: &str) -> bool {
+    value.get(0..10).is_some_and(is_date) && matches!(value.get(10..11), Some("T" | "t"))
+        && value.get(11..).is_some_and(is_time)
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value
+            .bytes()
+            .enumerate()
+            .all(|(index, x)| match index {
+                8 | 13 | 18 | 23 => x == b'-',
+                _ => x.is_ascii_hexdigit(),
+            })
+}
+
+fn is_uri(value: &str) -> bool {
+    let Some((scheme, _)) = value.split_once(':') else {
+        return false;
+    };
+    scheme.starts_with(|x: char| x.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || matches!(x, '+' | '-' | '.'))
+        && !value.chars().any(char::is_whitespace)
+}
+
+impl Validate for GetPetPath {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "pet_id");
+            let value = &self.pet_id;
+            check_length(&path, value, Some(3), None, errors);
+        }
+    }
+}
+
+// Error with details
+// -------------------------------
+
+/// Create detailed errors with ease
+#[macro_export]
+macro_rules! detailed {
+    ($err:expr,$msg:expr) => {
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
+    };
+}
+
+/// Bails with detailed api error
+#[macro_export]
+macro_rules! apibail {
+    ($err:expr,$msg:expr) => {
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
+    };
+}
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+impl<E: Display + Debug> ResponseError for Detailed<E>
+where
+    E: StatusCoded,
+{
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            })
+    }
+}
+
+/// Converts some result to Result with detailed api error
+pub trait ApiErr<T, E> {
+    /// Wrap the error value with additional context.
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static;
+}
+
+impl<T, E> ApiErr<T, E> for Result<T, E>
+where
+    E: Debug + Send + Sync + 'static,
+{
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
+        }
+    }
+}
+
+// Error
+// -------------------------------
+
+// Security
+// -------------------------------
+
+/// Rejection of the request by `Authenticator`
+#[derive(Debug)]
+pub enum AuthError {
+    /// Credentials are missing or invalid, responds with 401 Unauthorized
+    Unauthorized(String),
+    /// Credentials do not allow calling the operation, responds with 403 Forbidden
+    Forbidden(String),
+}
+
+impl AuthError {
+    fn missing() -> Self {
+        AuthError::Unauthorized("Credentials are missing".to_string())
+    }
+    /// Error responding with the rejection, `401` challenges the caller to authenticate
+    /// with the given schemes in `WWW-Authenticate` header
+    pub fn challenge(self, challenges: &[Option<&str>]) -> actix_web::Error {
+        let mut response = self.error_response();
+        let challenges: Vec<&str> = challenges.iter().flatten().copied().collect();
+        if matches!(self, AuthError::Unauthorized(_)) && !challenges.is_empty() {
+            if let Ok(value) = actix_web::http::header::HeaderValue::from_str(
+                &challenges.join(", "),
+            ) {
+                response
+                    .headers_mut()
+                    .insert(actix_web::http::header::WWW_AUTHENTICATE, value);
+            }
+        }
+        actix_web::error::InternalError::from_response(self, response).into()
+    }
+}
+
+impl Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unauthorized(reason) => write!(f, "Unauthorized: {reason}"),
+            Self::Forbidden(reason) => write!(f, "Forbidden: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+        }
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        let (Self::Unauthorized(reason) | Self::Forbidden(reason)) = self;
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: "about:blank".to_string(),
+                title: status.canonical_reason().unwrap_or_default().to_string(),
+                status: status.as_u16(),
+                detail: reason.clone(),
+                instance: None,
+            })
+    }
+}
+
+/// Credentials of the `Authorization` header with the given scheme
+fn authorization<'a>(req: &'a HttpRequest, scheme: &str) -> Option<&'a str> {
+    let value = req
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let (name, credentials) = value.split_once(' ')?;
+    match name.eq_ignore_ascii_case(scheme) {
+        true => Some(credentials.trim()),
+        false => None,
+    }
+}
+
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in value.trim_end_matches('=').bytes() {
+        let digit = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(digit);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// Credentials of `BearerAuth` security scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BearerAuth {
+    pub token: String,
+}
+
+impl BearerAuth {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = Some("Bearer");
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let token = authorization(req, "Bearer")?;
+        Some(BearerAuth {
+            token: token.to_string(),
+        })
+    }
+}
+
+/// Admin credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+impl BasicAuth {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = Some("Basic realm=\"BasicAuth\"");
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let encoded = authorization(req, "Basic")?;
+        let decoded = String::from_utf8(decode_base64(encoded)?).ok()?;
+        let (username, password) = decoded.split_once(':')?;
+        Some(BasicAuth {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+/// Credentials of `ApiKey` security scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    pub key: String,
+}
+
+impl ApiKey {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = None;
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let key = req.headers().get("X-API-Key")?.to_str().ok()?;
+        Some(ApiKey { key: key.to_string() })
+    }
+}
+
+/// Credentials of `QueryKey` security scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryKey {
+    pub key: String,
+}
+
+impl QueryKey {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = None;
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+            .ok()?;
+        let key = query.get("api_key")?;
+        Some(QueryKey { key: key.to_string() })
+    }
+}
+
+/// Credentials of `Session` security scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub key: String,
+}
+
+impl Session {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = None;
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let cookie = req.cookie("session_id")?;
+        let key = cookie.value();
+        Some(Session { key: key.to_string() })
+    }
+}
+
+/// Credentials of `PetstoreAuth` security scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PetstoreAuth {
+    pub token: String,
+}
+
+impl PetstoreAuth {
+    /// `WWW-Authenticate` challenge of the scheme, if it has a standard one
+    pub const CHALLENGE: Option<&'static str> = Some("Bearer");
+    /// Credentials sent with the request, if any
+    pub fn from_request(req: &HttpRequest) -> Option<Self> {
+        let token = authorization(req, "Bearer")?;
+        Some(PetstoreAuth {
+            token: token.to_string(),
+        })
+    }
+}
+
+/// Credentials `delete_pet` is called with,
+/// only schemes of the accepted security requirement are set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletePetCredentials {
+    pub basic_auth: Option<BasicAuth>,
+    pub bearer_auth: Option<BearerAuth>,
+    pub api_key: Option<ApiKey>,
+}
+
+/// Checks credentials of the security schemes declared in the spec
+/// before the operations requiring them are called
+#[async_trait(?Send)]
+pub trait Authenticator<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Check credentials of `BearerAuth` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_bearer_auth(
+        data: &web::Data<S>,
+        credentials: &BearerAuth,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+    /// Check credentials of `BasicAuth` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_basic_auth(
+        data: &web::Data<S>,
+        credentials: &BasicAuth,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+    /// Check credentials of `ApiKey` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_api_key(
+        data: &web::Data<S>,
+        credentials: &ApiKey,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+    /// Check credentials of `QueryKey` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_query_key(
+        data: &web::Data<S>,
+        credentials: &QueryKey,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+    /// Check credentials of `Session` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_session(
+        data: &web::Data<S>,
+        credentials: &Session,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+    /// Check credentials of `PetstoreAuth` security scheme,
+    /// scopes are the ones required by the operation
+    async fn authenticate_petstore_auth(
+        data: &web::Data<S>,
+        credentials: &PetstoreAuth,
+        scopes: &[&str],
+    ) -> Result<(), AuthError>;
+}
+
+// Api service
+// -------------------------------
+
+#[async_trait(?Send)]
+pub trait ApiService<S>: Authenticator<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// List pets visible to anyone, with more details for signed in users
+    async fn list_pets(
+        data: web::Data<S>,
+        auth: Option<Session>,
+    ) -> web::Json<Vec<String>>;
+    /// Add pet
+    async fn add_pet(
+        data: web::Data<S>,
+        auth: PetstoreAuth,
+        body: web::Json<String>,
+    ) -> web::Json<bool>;
+    /// Get pet with the default security
+    async fn get_pet(
+        data: web::Data<S>,
+        auth: BearerAuth,
+        path: web::Path<GetPetPath>,
+    ) -> web::Json<String>;
+    /// Delete pet as admin or with an api key sent along with the token
+    async fn delete_pet(
+        data: web::Data<S>,
+        auth: DeletePetCredentials,
+        path: web::Path<GetPetPath>,
+    ) -> web::Json<bool>;
+    /// Public status
+    async fn status(
+        data: web::Data<S>,
+        query: web::Query<StatusQuery>,
+    ) -> web::Json<String>;
+    /// Export with the query key
+    async fn export(data: web::Data<S>, auth: QueryKey) -> web::Json<String>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+// Run service function (+ helper functions)
+// -----------------------------------------
+
+static DOCS_OPENAPI: &str = include_str!("static/openapi.yaml");
+
+static DOCS_HTML: &str = include_str!("static/docs.html");
+
+async fn openapi() -> String {
+    DOCS_OPENAPI.to_string()
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::OK)
+        .content_type("text/html; charset=utf-8")
+        .body(DOCS_HTML)
+}
+
+async fn to_v1_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "v1/docs"))
+        .body("")
+}
+
+async fn to_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "docs"))
+        .body("")
+}
+
+// Tells that service is alive
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// Authenticate the caller of `list_pets` before calling the service
+async fn checked_list_pets<T, S>(
+    req: HttpRequest,
+    data: web::Data<S>,
+) -> Result<web::Json<Vec<String>>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let session_credentials = Session::from_request(&req);
+    let mut rejection = None;
+    let auth = 'auth: {
+        if let Some(session_credentials) = &session_credentials {
+            let result = T::authenticate_session(&data, session_credentials, &[]).await;
+            match result {
+                Ok(()) => break 'auth Some(session_credentials.clone()),
+                Err(error) => rejection = Some(error),
+            }
+        }
+        match rejection {
+            Some(error) => return Err(error.challenge(&[Session::CHALLENGE])),
+            None => None,
+        }
+    };
+    Ok(T::list_pets(data, auth).await)
+}
+
+/// Authenticate the caller of `add_pet` before calling the service
+async fn checked_add_pet<T, S>(
+    req: HttpRequest,
+    payload: web::Payload,
+    data: web::Data<S>,
+) -> Result<web::Json<bool>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let petstore_auth_credentials = PetstoreAuth::from_request(&req);
+    let mut rejection = None;
+    let auth = 'auth: {
+        if let Some(petstore_auth_credentials) = &petstore_auth_credentials {
+            let result = T::authenticate_petstore_auth(
+                    &data,
+                    petstore_auth_credentials,
+                    &["write:pets", "read:pets"],
+                )
+                .await;
+            match result {
+                Ok(()) => break 'auth petstore_auth_credentials.clone(),
+                Err(error) => rejection = Some(error),
+            }
+        }
+        match rejection {
+            Some(error) => return Err(error.challenge(&[PetstoreAuth::CHALLENGE])),
+            None => {
+                return Err(AuthError::missing().challenge(&[PetstoreAuth::CHALLENGE]));
+            }
+        }
+    };
+    let mut payload = payload.into_inner();
+    let body = <web::Json<
+        String,
+    > as actix_web::FromRequest>::from_request(&req, &mut payload)
+        .await?;
+    Ok(T::add_pet(data, auth, body).await)
+}
+
+/// Authenticate the caller of `get_pet` and check constraints of its arguments
+/// before calling the service
+async fn checked_get_pet<T, S>(
+    req: HttpRequest,
+    payload: web::Payload,
+    data: web::Data<S>,
+) -> Result<web::Json<String>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let bearer_auth_credentials = BearerAuth::from_request(&req);
+    let mut rejection = None;
+    let auth = 'auth: {
+        if let Some(bearer_auth_credentials) = &bearer_auth_credentials {
+            let result = T::authenticate_bearer_auth(&data, bearer_auth_credentials, &[])
+                .await;
+            match result {
+                Ok(()) => break 'auth bearer_auth_credentials.clone(),
+                Err(error) => rejection = Some(error),
+            }
+        }
+        match rejection {
+            Some(error) => return Err(error.challenge(&[BearerAuth::CHALLENGE])),
+            None => return Err(AuthError::missing().challenge(&[BearerAuth::CHALLENGE])),
+        }
+    };
+    let mut payload = payload.into_inner();
+    let path = <web::Path<
+        GetPetPath,
+    > as actix_web::FromRequest>::from_request(&req, &mut payload)
+        .await?;
+    let mut errors = Vec::new();
+    path.validate_into("path", &mut errors);
+    ValidationErrors::check(errors)?;
+    Ok(T::get_pet(data, auth, path).await)
+}
+
+/// Authenticate the caller of `delete_pet` and check constraints of its arguments
+/// before calling the service
+async fn checked_delete_pet<T, S>(
+    req: HttpRequest,
+    payload: web::Payload,
+    data: web::Data<S>,
+) -> Result<web::Json<bool>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let basic_auth_credentials = BasicAuth::from_request(&req);
+    let bearer_auth_credentials = BearerAuth::from_request(&req);
+    let api_key_credentials = ApiKey::from_request(&req);
+    let mut rejection = None;
+    let auth = 'auth: {
+        if let Some(basic_auth_credentials) = &basic_auth_credentials {
+            let result = T::authenticate_basic_auth(&data, basic_auth_credentials, &[])
+                .await;
+            match result {
+                Ok(()) => {
+                    break 'auth DeletePetCredentials {
+                        basic_auth: Some(basic_auth_credentials.clone()),
+                        bearer_auth: None,
+                        api_key: None,
+                    };
+                }
+                Err(error) => rejection = Some(error),
+            }
+        }
+        if let (Some(bearer_auth_credentials), Some(api_key_credentials)) = (
+            &bearer_auth_credentials,
+            &api_key_credentials,
+        ) {
+            let mut result = T::authenticate_bearer_auth(
+                    &data,
+                    bearer_auth_credentials,
+                    &[],
+                )
+                .await;
+            if result.is_ok() {
+                result = T::authenticate_api_key(&data, api_key_credentials, &[]).await;
+            }
+            match result {
+                Ok(()) => {
+                    break 'auth DeletePetCredentials {
+                        basic_auth: None,
+                        bearer_auth: Some(bearer_auth_credentials.clone()),
+                        api_key: Some(api_key_credentials.clone()),
+                    };
+                }
+                Err(error) => rejection = Some(error),
+            }
+        }
+        match rejection {
+            Some(error) => {
+                return Err(
+                    error
+                        .challenge(
+                            &[
+                                BasicAuth::CHALLENGE,
+                                BearerAuth::CHALLENGE,
+                                ApiKey::CHALLENGE,
+                            ],
+                        ),
+                );
+            }
+            None => {
+                return Err(
+                    AuthError::missing()
+                        .challenge(
+                            &[
+                                BasicAuth::CHALLENGE,
+                                BearerAuth::CHALLENGE,
+                                ApiKey::CHALLENGE,
+                            ],
+                        ),
+                );
+            }
+        }
+    };
+    let mut payload = payload.into_inner();
+    let path = <web::Path<
+        GetPetPath,
+    > as actix_web::FromRequest>::from_request(&req, &mut payload)
+        .await?;
+    let mut errors = Vec::new();
+    path.validate_into("path", &mut errors);
+    ValidationErrors::check(errors)?;
+    Ok(T::delete_pet(data, auth, path).await)
+}
+
+/// Authenticate the caller of `export` before calling the service
+async fn checked_export<T, S>(
+    req: HttpRequest,
+    data: web::Data<S>,
+) -> Result<web::Json<String>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let query_key_credentials = QueryKey::from_request(&req);
+    let mut rejection = None;
+    let auth = 'auth: {
+        if let Some(query_key_credentials) = &query_key_credentials {
+            let result = T::authenticate_query_key(&data, query_key_credentials, &[])
+                .await;
+            match result {
+                Ok(()) => break 'auth query_key_credentials.clone(),
+                Err(error) => rejection = Some(error),
+            }
+        }
+        match rejection {
+            Some(error) => return Err(error.challenge(&[QueryKey::CHALLENGE])),
+            None => return Err(AuthError::missing().challenge(&[QueryKey::CHALLENGE])),
+        }
+    };
+    Ok(T::export(data, auth).await)
+}
+
+/// Service of a single operation as seen by the metrics middleware
+pub type OperationService = Box<
+    dyn Service<
+        ServiceRequest,
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+        Future = std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                    Output = Result<ServiceResponse, actix_web::Error>,
+                >,
+            >,
+        >,
+    >,
+>;
+
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+pub fn make_scope<T, S, M, B>(metrics: M) -> actix_web::Scope
+where
+    T: ApiService<S> + 'static,
+    S: Send + Sync + 'static,
+    M: Transform<
+            OperationService,
+            ServiceRequest,
+            Response = ServiceResponse<B>,
+            Error = actix_web::Error,
+            InitError = (),
+        > + Clone + 'static,
+    B: MessageBody + 'static,
+{
+    use web::{delete, get, post};
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/export", actix_web::http::Method::GET),
+                            ("/pets", actix_web::http::Method::GET),
+                            ("/pets", actix_web::http::Method::POST),
+                            ("/pets/{pet_id}", actix_web::http::Method::GET),
+                            ("/pets/{pet_id}", actix_web::http::Method::DELETE),
+                            ("/status", actix_web::http::Method::GET),
+                            ("/v1/export", actix_web::http::Method::GET),
+                            ("/v1/pets", actix_web::http::Method::GET),
+                            ("/v1/pets", actix_web::http::Method::POST),
+                            ("/v1/pets/{pet_id}", actix_web::http::Method::GET),
+                            ("/v1/pets/{pet_id}", actix_web::http::Method::DELETE),
+                            ("/v1/status", actix_web::http::Method::GET),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/export")
+                        .name("export")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "export",
+                                    &[],
+                                    get().to(checked_export::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/pets")
+                        .name("list_pets")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "list_pets",
+                                    &[],
+                                    get().to(checked_list_pets::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/pets")
+                        .name("add_pet")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "add_pet",
+                                    &[],
+                                    post().to(checked_add_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/pets/{pet_id}")
+                        .name("get_pet")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "get_pet",
+                                    &[],
+                                    get().to(checked_get_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/pets/{pet_id}")
+                        .name("delete_pet")
+                        .guard(guard::Delete())
+                        .route(
+                            T::wrap_route(
+                                    "delete_pet",
+                                    &[],
+                                    delete().to(checked_delete_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/status")
+                        .name("status")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route("status", &[], get().to(T::status))
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/export")
+                        .name("export")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "export",
+                                    &[],
+                                    get().to(checked_export::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/pets")
+                        .name("list_pets")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "list_pets",
+                                    &[],
+                                    get().to(checked_list_pets::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/pets")
+                        .name("add_pet")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "add_pet",
+                                    &[],
+                                    post().to(checked_add_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/pets/{pet_id}")
+                        .name("get_pet")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "get_pet",
+                                    &[],
+                                    get().to(checked_get_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/pets/{pet_id}")
+                        .name("delete_pet")
+                        .guard(guard::Delete())
+                        .route(
+                            T::wrap_route(
+                                    "delete_pet",
+                                    &[],
+                                    delete().to(checked_delete_pet::<T, S>),
+                                )
+                                .wrap(metrics.clone()),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/status")
+                        .name("status")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route("status", &[], get().to(T::status))
+                                .wrap(metrics.clone()),
+                        ),
+                );
+            T::configure_tag("default", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        });
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .service(api)
+}
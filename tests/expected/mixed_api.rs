@@ -1,21 +1,17 @@
-#![allow(unused_imports)]
-
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
 //! API auto-generated by apigen
 
-use std::{
-    collections::HashMap,
-    fmt::{Debug, Display},
-};
+use std::{collections::HashMap, fmt::{Debug, Display}};
 
 use serde::{Deserialize, Serialize};
 
 use actix_web::{
-    http::StatusCode,
-    middleware::{NormalizePath, TrailingSlash},
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
     web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
 };
 
-use actix_web_prom::PrometheusMetricsBuilder;
+use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
 
 use async_trait::async_trait;
 
@@ -44,6 +40,115 @@ pub struct GoodbyeUserPathV2 {
     pub user: i64,
 }
 
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
 // Error with details
 // -------------------------------
 
@@ -51,10 +156,8 @@ pub struct GoodbyeUserPathV2 {
 #[macro_export]
 macro_rules! detailed {
     ($err:expr,$msg:expr) => {
-        $crate::server::api::Detailed {
-            error: $err,
-            details: $msg.to_string(),
-        }
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
     };
 }
 
@@ -62,21 +165,44 @@ macro_rules! detailed {
 #[macro_export]
 macro_rules! apibail {
     ($err:expr,$msg:expr) => {
-        return Err($crate::server::api::Detailed {
-            error: $err,
-            details: $msg.to_string(),
-        })
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
     };
 }
 
 pub trait StatusCoded {
     fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
 }
 
 #[derive(Debug)]
 pub struct Detailed<E> {
     pub error: E,
     pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
 }
 
 impl<E: Display> Display for Detailed<E> {
@@ -94,6 +220,18 @@ where
     fn status_code(&self) -> StatusCode {
         self.error.status_code()
     }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            })
+    }
 }
 
 /// Converts some result to Result with detailed api error
@@ -112,14 +250,15 @@ where
     where
         C: Display + Send + Sync + 'static,
     {
-        // Not using map_err to save 2 useless frames off the captured backtrace
-        // in ext_context.
         match self {
             Ok(ok) => Ok(ok),
-            Err(original_error) => Err(Detailed {
-                error: err,
-                details: format!("{:?}", original_error),
-            }),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
         }
     }
 }
@@ -150,34 +289,61 @@ where
         data: web::Data<S>,
         path: web::Path<GoodbyeUserPathV2>,
     ) -> web::Json<String>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
 }
 
 // Run service function (+ helper functions)
 // -----------------------------------------
+
 static DOCS_OPENAPI: &str = include_str!("static/openapi_v1.yaml");
+
 static DOCS_HTML: &str = include_str!("static/docs.html");
+
 static DOCS_OPENAPIV2: &str = include_str!("static/openapi_v2.yaml");
+
 async fn openapi() -> String {
     DOCS_OPENAPI.to_string()
 }
+
 async fn openapi_v2() -> String {
     DOCS_OPENAPIV2.to_string()
 }
+
 async fn docs() -> HttpResponse {
     HttpResponse::build(StatusCode::OK)
         .content_type("text/html; charset=utf-8")
         .body(DOCS_HTML)
 }
+
 async fn to_v1_docs() -> HttpResponse {
     HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
         .append_header(("Location", "v1/docs"))
         .body("")
 }
+
 async fn to_docs() -> HttpResponse {
     HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
         .append_header(("Location", "docs"))
         .body("")
 }
+
 async fn to_v2_docs() -> HttpResponse {
     HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
         .append_header(("Location", "v2/docs"))
@@ -185,59 +351,131 @@ async fn to_v2_docs() -> HttpResponse {
 }
 
 // Tells that service is alive
+
 async fn health() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
 
-pub async fn run_service<T, S>(bind: &str, initial_state: S) -> Result<(), std::io::Error>
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+pub fn make_scope<T, S>(prometheus: PrometheusMetrics) -> actix_web::Scope
 where
     T: ApiService<S> + 'static,
     S: Send + Sync + 'static,
 {
-    let app_data = web::Data::new(initial_state);
-
-    let prometheus = PrometheusMetricsBuilder::new("api")
-        .endpoint("/metrics")
-        .build()
-        .unwrap();
-
     use web::{delete, get, post};
-
-    HttpServer::new(move || {
-
-        let api = web::scope("")
-            .wrap(prometheus.clone())
-            .route("/goodbye/{user}", get().to(T::goodbye_user))
-            .route("/hello/{user}", get().to(T::greet_user))
-            .route("/v1/goodbye/{user}", get().to(T::goodbye_user))
-            .route("/v1/hello/{user}", get().to(T::greet_user))
-            .route("/v2/goodbye/{user}", get().to(T::goodbye_user_v2))
-            .route("/v2/hello/{user}", get().to(T::greet_user))
-            .wrap(prometheus.clone());
-
-        App::new()
-            .app_data(app_data.clone())
-            .wrap(NormalizePath::new(TrailingSlash::MergeOnly))
-            // Aux services
-            .route("/health", get().to(health))
-            // Static paths
-            .route("/", get().to(to_v2_docs))
-            .route("/docs", get().to(docs))
-            .route("/openapi.yaml", get().to(openapi))
-            .route("/v1", get().to(to_v1_docs))
-            .route("/v1/", get().to(to_docs))
-            .route("/v1/docs", get().to(docs))
-            .route("/v1/openapi.yaml", get().to(openapi))
-            .route("/v2", get().to(to_v2_docs))
-            .route("/v2/", get().to(to_docs))
-            .route("/v2/docs", get().to(docs))
-            .route("/v2/openapi.yaml", get().to(openapi_v2))
-            // Server routes
-            .service(api)
-    })
-    .bind(bind)?
-    .run()
-    .await?;
-
-    Ok(())
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/goodbye/{user}", actix_web::http::Method::GET),
+                            ("/hello/{user}", actix_web::http::Method::GET),
+                            ("/v1/goodbye/{user}", actix_web::http::Method::GET),
+                            ("/v1/hello/{user}", actix_web::http::Method::GET),
+                            ("/v2/goodbye/{user}", actix_web::http::Method::GET),
+                            ("/v2/hello/{user}", actix_web::http::Method::GET),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/goodbye/{user}")
+                        .name("goodbye_user")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route("goodbye_user", &[], get().to(T::goodbye_user)),
+                        ),
+                )
+                .service(
+                    web::resource("/hello/{user}")
+                        .name("greet_user")
+                        .guard(guard::Get())
+                        .route(T::wrap_route("greet_user", &[], get().to(T::greet_user))),
+                )
+                .service(
+                    web::resource("/v1/goodbye/{user}")
+                        .name("goodbye_user")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route("goodbye_user", &[], get().to(T::goodbye_user)),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/hello/{user}")
+                        .name("greet_user")
+                        .guard(guard::Get())
+                        .route(T::wrap_route("greet_user", &[], get().to(T::greet_user))),
+                )
+                .service(
+                    web::resource("/v2/goodbye/{user}")
+                        .name("goodbye_user_v2")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                "goodbye_user_v2",
+                                &[],
+                                get().to(T::goodbye_user_v2),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v2/hello/{user}")
+                        .name("greet_user")
+                        .guard(guard::Get())
+                        .route(T::wrap_route("greet_user", &[], get().to(T::greet_user))),
+                );
+            T::configure_tag("default", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        })
+        .wrap(prometheus);
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_v2_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .route("/v2", get().to(to_v2_docs))
+        .route("/v2/", get().to(to_docs))
+        .route("/v2/docs", get().to(docs))
+        .route("/v2/openapi.yaml", get().to(openapi_v2))
+        .service(api)
 }
@@ -0,0 +1,677 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use actix_web::{
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+
+use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
+
+use async_trait::async_trait;
+
+// Defaults
+// -------------------------------
+
+// Enums
+// -------------------------------
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CreateUserBody {
+    pub name: String,
+    pub nickname: Option<String>,
+}
+
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+// Content negotiation
+// -------------------------------
+
+/// Serialization format of the request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    Json,
+    Cbor,
+    MsgPack,
+}
+
+/// Media types accepted by the request body
+pub trait MediaTypes {
+    const ACCEPTED: &'static [(&'static str, BodyFormat)];
+}
+
+/// Request body deserialized according to its `Content-Type` header.
+/// Responds with 415 Unsupported Media Type if the content type is not accepted
+#[derive(Debug)]
+pub struct Negotiated<T, M> {
+    inner: T,
+    format: BodyFormat,
+    media: std::marker::PhantomData<M>,
+}
+
+impl<T, M> Negotiated<T, M> {
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+    /// Format the body was sent in
+    pub fn format(&self) -> BodyFormat {
+        self.format
+    }
+}
+
+impl<T, M> std::ops::Deref for Negotiated<T, M> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T, M> std::ops::DerefMut for Negotiated<T, M> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T, M> actix_web::FromRequest for Negotiated<T, M>
+where
+    T: serde::de::DeserializeOwned + 'static,
+    M: MediaTypes + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self, Self::Error>>>,
+    >;
+    fn from_request(
+        req: &HttpRequest,
+        payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let content_type = req
+            .headers()
+            .get(actix_web::http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase());
+        let format = M::ACCEPTED
+            .iter()
+            .find(|(media_type, _)| Some(*media_type) == content_type.as_deref())
+            .map(|(_, format)| *format);
+        let bytes = web::Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let Some(format) = format else {
+                let accepted: Vec<&str> = M::ACCEPTED
+                    .iter()
+                    .map(|(media_type, _)| *media_type)
+                    .collect();
+                return Err(
+                    actix_web::error::ErrorUnsupportedMediaType(
+                        format!(
+                            "Unsupported content type {:?}. Expected one of: {}",
+                            content_type.unwrap_or_default(), accepted.join(", ")
+                        ),
+                    ),
+                );
+            };
+            let bytes = bytes.await?;
+            let inner = match format {
+                BodyFormat::Json => {
+                    serde_json::from_slice(&bytes)
+                        .map_err(|err| request_error("body", err.to_string()))?
+                }
+                BodyFormat::Cbor => {
+                    ciborium::de::from_reader(bytes.as_ref())
+                        .map_err(|err| request_error("body", err.to_string()))?
+                }
+                BodyFormat::MsgPack => {
+                    rmp_serde::from_slice(&bytes)
+                        .map_err(|err| request_error("body", err.to_string()))?
+                }
+            };
+            Ok(Negotiated {
+                inner,
+                format,
+                media: std::marker::PhantomData,
+            })
+        })
+    }
+}
+
+pub struct JsonCborMsgpackMedia;
+
+impl MediaTypes for JsonCborMsgpackMedia {
+    const ACCEPTED: &'static [(&'static str, BodyFormat)] = &[
+        ("application/json", BodyFormat::Json),
+        ("application/cbor", BodyFormat::Cbor),
+        ("application/msgpack", BodyFormat::MsgPack),
+    ];
+}
+
+pub struct JsonCborMedia;
+
+impl MediaTypes for JsonCborMedia {
+    const ACCEPTED: &'static [(&'static str, BodyFormat)] = &[
+        ("application/json", BodyFormat::Json),
+        ("application/cbor", BodyFormat::Cbor),
+    ];
+}
+
+// Error with details
+// -------------------------------
+
+/// Create detailed errors with ease
+#[macro_export]
+macro_rules! detailed {
+    ($err:expr,$msg:expr) => {
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
+    };
+}
+
+/// Bails with detailed api error
+#[macro_export]
+macro_rules! apibail {
+    ($err:expr,$msg:expr) => {
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
+    };
+}
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+impl<E: Display + Debug> ResponseError for Detailed<E>
+where
+    E: StatusCoded,
+{
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            })
+    }
+}
+
+/// Converts some result to Result with detailed api error
+pub trait ApiErr<T, E> {
+    /// Wrap the error value with additional context.
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static;
+}
+
+impl<T, E> ApiErr<T, E> for Result<T, E>
+where
+    E: Debug + Send + Sync + 'static,
+{
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
+        }
+    }
+}
+
+// Error
+// -------------------------------
+
+// Api service
+// -------------------------------
+
+#[async_trait(?Send)]
+pub trait ApiService<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Create user from any of the supported formats
+    async fn create_user(
+        data: web::Data<S>,
+        body: Negotiated<CreateUserBody, JsonCborMsgpackMedia>,
+    ) -> web::Json<CreateUserBody>;
+    /// Create several users at once
+    async fn create_users(
+        data: web::Data<S>,
+        body: Negotiated<Vec<CreateUserBody>, JsonCborMedia>,
+    ) -> web::Json<i64>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+// Run service function (+ helper functions)
+// -----------------------------------------
+
+static DOCS_OPENAPI: &str = include_str!("static/openapi.yaml");
+
+static DOCS_HTML: &str = include_str!("static/docs.html");
+
+async fn openapi() -> String {
+    DOCS_OPENAPI.to_string()
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::OK)
+        .content_type("text/html; charset=utf-8")
+        .body(DOCS_HTML)
+}
+
+async fn to_v1_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "v1/docs"))
+        .body("")
+}
+
+async fn to_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "docs"))
+        .body("")
+}
+
+// Tells that service is alive
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+pub fn make_scope<T, S>(prometheus: PrometheusMetrics) -> actix_web::Scope
+where
+    T: ApiService<S> + 'static,
+    S: Send + Sync + 'static,
+{
+    use web::{delete, get, post};
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/users", actix_web::http::Method::POST),
+                            ("/users/batch", actix_web::http::Method::POST),
+                            ("/v1/users", actix_web::http::Method::POST),
+                            ("/v1/users/batch", actix_web::http::Method::POST),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/users")
+                        .name("create_user")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route("create_user", &[], post().to(T::create_user)),
+                        ),
+                )
+                .service(
+                    web::resource("/users/batch")
+                        .name("create_users")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                "create_users",
+                                &[],
+                                post().to(T::create_users),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/users")
+                        .name("create_user")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route("create_user", &[], post().to(T::create_user)),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/users/batch")
+                        .name("create_users")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                "create_users",
+                                &[],
+                                post().to(T::create_users),
+                            ),
+                        ),
+                );
+            T::configure_tag("default", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        })
+        .wrap(prometheus);
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .service(api)
+}
+
+// Mock service
+// -------------------------------
+
+/// Implementation of `ApiService` with programmable responses, for use in tests
+pub mod mock {
+    use std::{collections::VecDeque, sync::Mutex};
+    use super::*;
+    type Responder<A, R> = Box<dyn FnMut(&A) -> R + Send>;
+    type Response<A, R> = Box<dyn FnOnce(&A) -> R + Send>;
+    /// Programmed responses and received arguments of a single operation
+    pub struct MockOperation<A, R> {
+        name: &'static str,
+        calls: Mutex<Vec<A>>,
+        responses: Mutex<VecDeque<Response<A, R>>>,
+        responder: Mutex<Option<Responder<A, R>>>,
+    }
+    impl<A: Clone, R> MockOperation<A, R> {
+        fn new(name: &'static str) -> Self {
+            MockOperation {
+                name,
+                calls: Mutex::new(Vec::new()),
+                responses: Mutex::new(VecDeque::new()),
+                responder: Mutex::new(None),
+            }
+        }
+        /// Respond to every call with the result of the closure
+        pub fn respond_with(&self, responder: impl FnMut(&A) -> R + Send + 'static) {
+            *self.responder.lock().unwrap() = Some(Box::new(responder));
+        }
+        /// Respond to the next call with the given response.
+        /// Queued responses are used before the closure set with `respond_with`
+        pub fn push_response(&self, response: R)
+        where
+            R: Send + 'static,
+        {
+            self.responses.lock().unwrap().push_back(Box::new(move |_| response));
+        }
+        /// Arguments of every call received so far
+        pub fn calls(&self) -> Vec<A> {
+            self.calls.lock().unwrap().clone()
+        }
+        pub fn call_count(&self) -> usize {
+            self.calls.lock().unwrap().len()
+        }
+        fn call(&self, args: A) -> R {
+            let queued = self.responses.lock().unwrap().pop_front();
+            let response = match queued {
+                Some(response) => response(&args),
+                None => {
+                    match self.responder.lock().unwrap().as_mut() {
+                        Some(responder) => responder(&args),
+                        None => {
+                            panic!("No response programmed for operation {}", self.name)
+                        }
+                    }
+                }
+            };
+            self.calls.lock().unwrap().push(args);
+            response
+        }
+    }
+    /// Arguments received by `create_user`
+    #[derive(Debug, Clone)]
+    pub struct CreateUserCall {
+        pub body: CreateUserBody,
+    }
+    /// Arguments received by `create_users`
+    #[derive(Debug, Clone)]
+    pub struct CreateUsersCall {
+        pub body: Vec<CreateUserBody>,
+    }
+    /// Service whose operations respond with programmed responses.
+    /// Register it as app data and use as both service and state:
+    /// `make_scope::<MockApiService, _>`
+    pub struct MockApiService {
+        pub create_user: MockOperation<CreateUserCall, web::Json<CreateUserBody>>,
+        pub create_users: MockOperation<CreateUsersCall, web::Json<i64>>,
+    }
+    impl Default for MockApiService {
+        fn default() -> Self {
+            MockApiService {
+                create_user: MockOperation::new("create_user"),
+                create_users: MockOperation::new("create_users"),
+            }
+        }
+    }
+    #[async_trait(?Send)]
+    impl ApiService<MockApiService> for MockApiService {
+        async fn create_user(
+            data: web::Data<MockApiService>,
+            body: Negotiated<CreateUserBody, JsonCborMsgpackMedia>,
+        ) -> web::Json<CreateUserBody> {
+            let body = body.into_inner();
+            data.create_user.call(CreateUserCall { body })
+        }
+        async fn create_users(
+            data: web::Data<MockApiService>,
+            body: Negotiated<Vec<CreateUserBody>, JsonCborMedia>,
+        ) -> web::Json<i64> {
+            let body = body.into_inner();
+            data.create_users.call(CreateUsersCall { body })
+        }
+    }
+}
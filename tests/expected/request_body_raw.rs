@@ -0,0 +1,633 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use actix_web::{
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+
+use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
+
+use async_trait::async_trait;
+
+// Defaults
+// -------------------------------
+
+// Enums
+// -------------------------------
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    pub value: Option<i64>,
+}
+
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+// Streaming request bodies
+// -------------------------------
+
+/// Media types of newline delimited json bodies accepted by `NdJson`
+const NDJSON_MEDIA_TYPES: [&str; 4] = [
+    "application/x-ndjson",
+    "application/ndjson",
+    "application/jsonl",
+    "application/x-jsonlines",
+];
+
+/// Limits of newline delimited json bodies, taken from `app_data` of the scope or resource
+#[derive(Debug, Clone, Copy)]
+pub struct NdJsonConfig {
+    /// Maximum length of a single line, 256 KiB by default as for the other bodies
+    pub line_limit: usize,
+}
+
+impl Default for NdJsonConfig {
+    fn default() -> Self {
+        NdJsonConfig {
+            line_limit: 262_144,
+        }
+    }
+}
+
+/// Newline delimited json request body.
+/// Yields items as they arrive, buffering at most one line of `NdJsonConfig::line_limit`
+pub struct NdJson<T> {
+    payload: actix_web::dev::Payload,
+    buffer: web::BytesMut,
+    done: bool,
+    /// Index of the next item, reported as `body[index]` in the errors
+    index: usize,
+    line_limit: usize,
+    /// Collects constraint violations of every item, as declared in the spec
+    check: Option<fn(&T, &str, &mut Vec<FieldError>)>,
+    item: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> actix_web::FromRequest for NdJson<T> {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+    fn from_request(
+        req: &HttpRequest,
+        payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let content_type = actix_web::HttpMessage::content_type(req);
+        if !NDJSON_MEDIA_TYPES.contains(&content_type) {
+            return std::future::ready(
+                Err(
+                    actix_web::error::ErrorUnsupportedMediaType(
+                        format!(
+                            "Expected newline delimited json body, got {content_type:?}"
+                        ),
+                    ),
+                ),
+            );
+        }
+        let config = req.app_data::<NdJsonConfig>().copied().unwrap_or_default();
+        std::future::ready(
+            Ok(NdJson {
+                payload: payload.take(),
+                buffer: web::BytesMut::new(),
+                done: false,
+                index: 0,
+                line_limit: config.line_limit,
+                check: None,
+                item: std::marker::PhantomData,
+            }),
+        )
+    }
+}
+
+impl<T> NdJson<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    /// Checks every item with the function collecting its constraint violations,
+    /// an invalid item is yielded as `422` error naming it as `body[index]`
+    pub fn checked(mut self, check: fn(&T, &str, &mut Vec<FieldError>)) -> Self {
+        self.check = Some(check);
+        self
+    }
+    fn parse_line(&mut self, line: &[u8]) -> Result<T, actix_web::Error> {
+        let source = format!("body[{}]", self.index);
+        self.index += 1;
+        let value = serde_json::from_slice(line)
+            .map_err(|err| request_error(&source, err.to_string()))?;
+        if let Some(check) = self.check {
+            let mut errors = Vec::new();
+            check(&value, &source, &mut errors);
+            ValidationErrors::check(errors)?;
+        }
+        Ok(value)
+    }
+    /// Stops the stream with the error, so that the rest of the body is not parsed
+    fn fail(
+        &mut self,
+        error: impl Into<actix_web::Error>,
+    ) -> Option<Result<T, actix_web::Error>> {
+        self.done = true;
+        self.buffer.clear();
+        Some(Err(error.into()))
+    }
+}
+
+impl<T> futures::Stream for NdJson<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Item = Result<T, actix_web::Error>;
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+        let this = self.get_mut();
+        loop {
+            let position = this.buffer.iter().position(|x| *x == b'\n');
+            if position.unwrap_or(this.buffer.len()) > this.line_limit {
+                return Poll::Ready(this.fail(actix_web::error::PayloadError::Overflow));
+            }
+            if let Some(position) = position {
+                let line = this.buffer.split_to(position + 1);
+                let line = line.trim_ascii();
+                if line.is_empty() {
+                    continue;
+                }
+                return Poll::Ready(Some(this.parse_line(line)));
+            }
+            if this.done {
+                let line = this.buffer.split();
+                let line = line.trim_ascii();
+                if line.is_empty() {
+                    return Poll::Ready(None);
+                }
+                return Poll::Ready(Some(this.parse_line(line)));
+            }
+            match std::pin::Pin::new(&mut this.payload).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => this.buffer.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(this.fail(err)),
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// Error with details
+// -------------------------------
+
+/// Create detailed errors with ease
+#[macro_export]
+macro_rules! detailed {
+    ($err:expr,$msg:expr) => {
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
+    };
+}
+
+/// Bails with detailed api error
+#[macro_export]
+macro_rules! apibail {
+    ($err:expr,$msg:expr) => {
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
+    };
+}
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+impl<E: Display + Debug> ResponseError for Detailed<E>
+where
+    E: StatusCoded,
+{
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            })
+    }
+}
+
+/// Converts some result to Result with detailed api error
+pub trait ApiErr<T, E> {
+    /// Wrap the error value with additional context.
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static;
+}
+
+impl<T, E> ApiErr<T, E> for Result<T, E>
+where
+    E: Debug + Send + Sync + 'static,
+{
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
+        }
+    }
+}
+
+// Error
+// -------------------------------
+
+// Api service
+// -------------------------------
+
+#[async_trait(?Send)]
+pub trait ApiService<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Upload large file without buffering it
+    async fn upload(
+        data: web::Data<S>,
+        body: web::Payload,
+    ) -> Result<web::Json<i64>, actix_web::Error>;
+    /// Upload small image
+    async fn upload_thumbnail(data: web::Data<S>, body: web::Bytes) -> web::Json<bool>;
+    /// Add plain text note
+    async fn add_note(data: web::Data<S>, body: String) -> web::Json<bool>;
+    /// Ingest newline delimited events
+    async fn ingest_events(
+        data: web::Data<S>,
+        body: NdJson<Event>,
+    ) -> Result<web::Json<i64>, actix_web::Error>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+// Run service function (+ helper functions)
+// -----------------------------------------
+
+static DOCS_OPENAPI: &str = include_str!("static/openapi.yaml");
+
+static DOCS_HTML: &str = include_str!("static/docs.html");
+
+async fn openapi() -> String {
+    DOCS_OPENAPI.to_string()
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::OK)
+        .content_type("text/html; charset=utf-8")
+        .body(DOCS_HTML)
+}
+
+async fn to_v1_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "v1/docs"))
+        .body("")
+}
+
+async fn to_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "docs"))
+        .body("")
+}
+
+// Tells that service is alive
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+pub fn make_scope<T, S>(prometheus: PrometheusMetrics) -> actix_web::Scope
+where
+    T: ApiService<S> + 'static,
+    S: Send + Sync + 'static,
+{
+    use web::{delete, get, post};
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/events", actix_web::http::Method::POST),
+                            ("/notes", actix_web::http::Method::POST),
+                            ("/thumbnail", actix_web::http::Method::POST),
+                            ("/upload", actix_web::http::Method::POST),
+                            ("/v1/events", actix_web::http::Method::POST),
+                            ("/v1/notes", actix_web::http::Method::POST),
+                            ("/v1/thumbnail", actix_web::http::Method::POST),
+                            ("/v1/upload", actix_web::http::Method::POST),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/events")
+                        .name("ingest_events")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                "ingest_events",
+                                &[],
+                                post().to(T::ingest_events),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/notes")
+                        .name("add_note")
+                        .guard(guard::Post())
+                        .route(T::wrap_route("add_note", &[], post().to(T::add_note))),
+                )
+                .service(
+                    web::resource("/thumbnail")
+                        .name("upload_thumbnail")
+                        .guard(guard::Post())
+                        .app_data(web::PayloadConfig::new(65536))
+                        .route(
+                            T::wrap_route(
+                                "upload_thumbnail",
+                                &[],
+                                post().to(T::upload_thumbnail),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/upload")
+                        .name("upload")
+                        .guard(guard::Post())
+                        .route(T::wrap_route("upload", &[], post().to(T::upload))),
+                )
+                .service(
+                    web::resource("/v1/events")
+                        .name("ingest_events")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                "ingest_events",
+                                &[],
+                                post().to(T::ingest_events),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/notes")
+                        .name("add_note")
+                        .guard(guard::Post())
+                        .route(T::wrap_route("add_note", &[], post().to(T::add_note))),
+                )
+                .service(
+                    web::resource("/v1/thumbnail")
+                        .name("upload_thumbnail")
+                        .guard(guard::Post())
+                        .app_data(web::PayloadConfig::new(65536))
+                        .route(
+                            T::wrap_route(
+                                "upload_thumbnail",
+                                &[],
+                                post().to(T::upload_thumbnail),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/upload")
+                        .name("upload")
+                        .guard(guard::Post())
+                        .route(T::wrap_route("upload", &[], post().to(T::upload))),
+                );
+            T::configure_tag("default", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        })
+        .wrap(prometheus);
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .service(api)
+}
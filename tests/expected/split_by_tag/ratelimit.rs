@@ -0,0 +1,1368 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use actix_web::{
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+
+use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
+
+use async_trait::async_trait;
+
+// Defaults
+// -------------------------------
+
+// Enums
+// -------------------------------
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuotaDetailsPath {
+    /// Quota label - Unique quota identifier
+    pub quota: String,
+}
+
+/// Quota specification
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Quota {
+    /// The 'weight' of a single cell in milliseconds or emission interval.
+    /// Maximum allowed requests per minute can be calculated as: 60 * 1000 / replanish_interval
+    /// Controls sustainable Cell Rate
+    pub replanish_interval: f64,
+    /// Number of sequential cells allowed in a single burst
+    /// A burst or clump of packets can arrive at a higher rate than determined by the emission interval
+    /// In case there is unused burst capacity, quota can also exceed RPM in certain time frames.
+    /// Burst capacity of 0 ensure that RPM is never exceeded but introduces a lot of delay.
+    /// Burst capacity does not affect Sustainable Cell Rate
+    pub burst_capacity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct MatchRule {}
+
+/// State information of the quota
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuotaState {
+    /// Earliest delay in ms from now when next cell is available
+    pub earliest_next_available: f64,
+    /// Current remaining burst capacity
+    pub remaining_burst_capacity: i64,
+}
+
+/// Quota statistics, purely descriptive. Not used in Rate limiting decisions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuotaStats {
+    /// Number of requests in last 60 seconds
+    pub rpm: i64,
+}
+
+/// Full information about quota
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuotaDetails {
+    /// Quota specification
+    pub quota: Quota,
+    /// Collection of predicates to test agains incomming queries
+    /// If at least one predicate is matching the incomming query, the rate limit is applied to the request
+    /// Multiple rate limits can be applied to incomming request at once
+    #[serde(rename = "match")]
+    pub match_: Vec<MatchRule>,
+    /// State information of the quota
+    pub state: QuotaState,
+    /// Quota statistics, purely descriptive. Not used in Rate limiting decisions.
+    pub stats: QuotaStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CellTestQuery {
+    /// Query that will be matched against quotas
+    ///
+    /// # Matching rules
+    /// ---------------
+    ///
+    /// Quota matches the query if at least one of it's predicates (match section) matches the query.
+    /// Predicate matches query if all its key/values are present and match key/values of the request query.
+    /// If query key is not present in the predicate, it is disregarded.
+    ///
+    /// ## Example:
+    ///
+    /// given predicate:
+    ///
+    ///
+    /// `
+    /// {
+    ///   'carrier': 'MEGB'
+    ///   'endpoint': 'location'
+    /// }
+    /// `
+    ///
+    /// match results on queries:
+    ///
+    ///
+    /// `?carrier=MEGB&endpoint=locations` - OK
+    ///
+    ///
+    /// `?carrier=MEGB` - No match
+    ///
+    ///
+    /// `?carrier=MEGB&endpoint=locations&sender=retailer-api` - OK
+    ///
+    ///
+    /// `?carrier=MEGB&sender=retailer-api` - No match
+    ///
+    ///
+    /// `?sender=retailer-api` - No match
+    pub query: MatchRule,
+}
+
+/// Information about current cell state and matched quotas.
+/// Matched quotas are computed based on query.
+/// Info and state are computed dynamically based on matched quotas.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CellDetails {
+    /// Matched quotas
+    pub quotas: Vec<String>,
+    /// Quota specification
+    pub info: Quota,
+    /// State information of the quota
+    pub state: QuotaState,
+}
+
+/// Information about current cell state.
+/// Info and state are computed dynamically based on matched quotas.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CellInfo {
+    /// Quota specification
+    pub info: Quota,
+    /// State information of the quota
+    pub state: QuotaState,
+}
+
+/// Result of the cell update. Allowed/Denied flag + cell info
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpdateResult {
+    /// Indicates if request was allowed
+    /// If request was allowed, rate limit state was already updated to accomodate
+    /// this request.
+    /// If request was rejected, rate limit was not updated
+    pub allowed: bool,
+    /// Information about current cell state.
+    /// Info and state are computed dynamically based on matched quotas.
+    pub details: CellInfo,
+}
+
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+// Error with details
+// -------------------------------
+
+/// Create detailed errors with ease
+#[macro_export]
+macro_rules! detailed {
+    ($err:expr,$msg:expr) => {
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
+    };
+}
+
+/// Bails with detailed api error
+#[macro_export]
+macro_rules! apibail {
+    ($err:expr,$msg:expr) => {
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
+    };
+}
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+impl<E: Display + Debug> ResponseError for Detailed<E>
+where
+    E: StatusCoded,
+{
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            })
+    }
+}
+
+/// Converts some result to Result with detailed api error
+pub trait ApiErr<T, E> {
+    /// Wrap the error value with additional context.
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static;
+}
+
+impl<T, E> ApiErr<T, E> for Result<T, E>
+where
+    E: Debug + Send + Sync + 'static,
+{
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
+        }
+    }
+}
+
+// Error
+// -------------------------------
+
+/// Status NOT_FOUND:
+/// Quota not found
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum QuotaDetailsError {
+    QuotaNotFound,
+}
+
+impl Display for QuotaDetailsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::QuotaNotFound => "Quota not found",
+        };
+        f.write_str(message)
+    }
+}
+
+impl StatusCoded for QuotaDetailsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::QuotaNotFound => StatusCode::NOT_FOUND,
+        }
+    }
+    fn problem_type(&self) -> &'static str {
+        match self {
+            Self::QuotaNotFound => "urn:problem:quota-details-error:quota-not-found",
+        }
+    }
+}
+
+/// Status BAD_REQUEST:
+/// Duplicate key in query
+///
+/// Status NOT_FOUND:
+/// No quotas matching given query found
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum CellTestError {
+    DuplicateQueryKey,
+    NoQuotasMatchingQueryFound,
+}
+
+impl Display for CellTestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::DuplicateQueryKey => "Duplicate query key",
+            Self::NoQuotasMatchingQueryFound => "No quotas matching query found",
+        };
+        f.write_str(message)
+    }
+}
+
+impl StatusCoded for CellTestError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::DuplicateQueryKey => StatusCode::BAD_REQUEST,
+            Self::NoQuotasMatchingQueryFound => StatusCode::NOT_FOUND,
+        }
+    }
+    fn problem_type(&self) -> &'static str {
+        match self {
+            Self::DuplicateQueryKey => "urn:problem:cell-test-error:duplicate-query-key",
+            Self::NoQuotasMatchingQueryFound => {
+                "urn:problem:cell-test-error:no-quotas-matching-query-found"
+            }
+        }
+    }
+}
+
+// Api service
+// -------------------------------
+
+/// Operations tagged `health` in the spec
+#[async_trait(?Send)]
+pub trait HealthApi<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Check service health
+    async fn health(data: web::Data<S>) -> web::Json<String>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+/// Operations tagged `quota` in the spec
+#[async_trait(?Send)]
+pub trait QuotaApi<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// List quotas
+    async fn quota_list(data: web::Data<S>) -> web::Json<Vec<String>>;
+    /// Get quota details
+    async fn quota_details(
+        data: web::Data<S>,
+        path: web::Path<QuotaDetailsPath>,
+    ) -> Result<web::Json<QuotaDetails>, Detailed<QuotaDetailsError>>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+/// Operations tagged `rate limiting` in the spec
+#[async_trait(?Send)]
+pub trait RateLimitingApi<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Get current rate limitation state for given query
+    async fn cell_test(
+        data: web::Data<S>,
+        query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<CellDetails>, Detailed<CellTestError>>;
+    /// Try to accomodate for one request
+    async fn cell_update(
+        data: web::Data<S>,
+        query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<UpdateResult>, Detailed<CellTestError>>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+// Run service function (+ helper functions)
+// -----------------------------------------
+
+static DOCS_OPENAPI: &str = "openapi: 3.0.3
+info:
+  title: ratelimit
+  description: |
+    Limits rates (with Rust)
+    Implements Leaky bucket using Generic cell rate algorithm (GCRA).
+    Allows for request bursts.
+  version: 1.0.0
+servers:
+- url: ./
+paths:
+  /health:
+    get:
+      tags:
+      - health
+      summary: Check service health
+      operationId: health
+      responses:
+        '200':
+          description: Service is ready
+          content:
+            application/json:
+              schema:
+                default: Ok
+                type: string
+  /quota:
+    get:
+      tags:
+      - quota
+      summary: List quotas
+      operationId: quota_list
+      responses:
+        '200':
+          description: List of all quotas
+          content:
+            application/json:
+              schema:
+                example:
+                - megb
+                - testquota
+                - anotherquota
+                title: Response Quota List Quota Get
+                type: array
+                items:
+                  type: string
+  /quota/{quota}:
+    get:
+      tags:
+      - quota
+      summary: Get quota details
+      operationId: quota_details
+      parameters:
+      - $ref: '#/components/parameters/quota'
+      responses:
+        '200':
+          description: Quota details, including current rpm and GCRA state
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/QuotaDetails'
+        '404':
+          description: Quota not found
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                - $ref: '#/components/schemas/ProblemDetails'
+                - type: object
+                  properties:
+                    title:
+                      title: Details
+                      type: string
+                      enum:
+                      - Quota not found
+  /cell/test:
+    get:
+      tags:
+      - rate limiting
+      summary: Get current rate limitation state for given query
+      description: |
+        Allows to probe for rate limits without updating quota states.
+        Usefull for monitoring.
+      operationId: cell_test
+      parameters:
+      - $ref: '#/components/parameters/query'
+      responses:
+        '200':
+          description: Successful Response
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/CellDetails'
+        '400':
+          description: Duplicate key in query
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                - $ref: '#/components/schemas/ProblemDetails'
+                - type: object
+                  properties:
+                    title:
+                      title: Details
+                      type: string
+                      enum:
+                      - Duplicate query key
+        '404':
+          description: No quotas matching given query found
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                - $ref: '#/components/schemas/ProblemDetails'
+                - type: object
+                  properties:
+                    title:
+                      title: Details
+                      type: string
+                      enum:
+                      - No quotas matching query found
+  /cell/update:
+    post:
+      tags:
+      - rate limiting
+      summary: Try to accomodate for one request
+      description: |
+        Will update states of all quotas that match given query.
+        Strictest rate limitation of all matched queries applies
+      operationId: cell_update
+      parameters:
+      - $ref: '#/components/parameters/query'
+      responses:
+        '200':
+          description: Successful Response
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/UpdateResult'
+        '400':
+          description: Duplicate key in query
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                - $ref: '#/components/schemas/ProblemDetails'
+                - type: object
+                  properties:
+                    title:
+                      title: Details
+                      type: string
+                      enum:
+                      - Duplicate query key
+        '404':
+          description: No quotas matching given query found
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                - $ref: '#/components/schemas/ProblemDetails'
+                - type: object
+                  properties:
+                    title:
+                      title: Details
+                      type: string
+                      enum:
+                      - No quotas matching query found
+components:
+  parameters:
+    quota:
+      in: path
+      name: quota
+      description: Quota label - Unique quota identifier
+      required: true
+      schema:
+        example: testquota
+        title: Quota
+        type: string
+      style: simple
+    query:
+      in: query
+      name: query
+      description: |
+        Query that will be matched against quotas
+
+        # Matching rules
+        ---------------
+
+        Quota matches the query if at least one of it's predicates (match section) matches the query.
+        Predicate matches query if all its key/values are present and match key/values of the request query.
+        If query key is not present in the predicate, it is disregarded.
+
+        ## Example:
+
+        given predicate:
+
+
+        `
+        {
+          'carrier': 'MEGB'
+          'endpoint': 'location'
+        }
+        `
+
+        match results on queries:
+
+
+        `?carrier=MEGB&endpoint=locations` - OK
+
+
+        `?carrier=MEGB` - No match
+
+
+        `?carrier=MEGB&endpoint=locations&sender=retailer-api` - OK
+
+
+        `?carrier=MEGB&sender=retailer-api` - No match
+
+
+        `?sender=retailer-api` - No match
+      required: true
+      schema:
+        $ref: '#/components/schemas/MatchRule'
+      style: form
+  schemas:
+    MatchRule:
+      example:
+        agency: testagency
+        carrier: TEST
+        endpoint: someendpoint
+      title: MatchRule
+      type: object
+      additionalProperties:
+        type: string
+    MatchRules:
+      title: MatchRules
+      description: |
+        Collection of predicates to test agains incomming queries
+        If at least one predicate is matching the incomming query, the rate limit is applied to the request
+        Multiple rate limits can be applied to incomming request at once
+      type: array
+      items:
+        $ref: '#/components/schemas/MatchRule'
+    Quota:
+      title: Quota
+      description: Quota specification
+      type: object
+      properties:
+        replanish_interval:
+          example: 1000.0
+          title: Replanish Interval
+          description: |
+            The 'weight' of a single cell in milliseconds or emission interval.
+            Maximum allowed requests per minute can be calculated as: 60 * 1000 / replanish_interval
+            Controls sustainable Cell Rate
+          type: number
+        burst_capacity:
+          example: 5
+          title: Burst Capacity
+          description: |
+            Number of sequential cells allowed in a single burst
+            A burst or clump of packets can arrive at a higher rate than determined by the emission interval
+            In case there is unused burst capacity, quota can also exceed RPM in certain time frames.
+            Burst capacity of 0 ensure that RPM is never exceeded but introduces a lot of delay.
+            Burst capacity does not affect Sustainable Cell Rate
+          type: integer
+      required:
+      - replanish_interval
+      - burst_capacity
+    QuotaDetails:
+      title: QuotaDetails
+      description: Full information about quota
+      type: object
+      properties:
+        quota:
+          $ref: '#/components/schemas/Quota'
+        match:
+          $ref: '#/components/schemas/MatchRules'
+        state:
+          $ref: '#/components/schemas/QuotaState'
+        stats:
+          $ref: '#/components/schemas/QuotaStats'
+      required:
+      - quota
+      - match
+      - state
+      - stats
+    QuotaState:
+      title: QuotaState
+      description: State information of the quota
+      type: object
+      properties:
+        earliest_next_available:
+          example: 566.66
+          title: Earliest Next Available
+          description: Earliest delay in ms from now when next cell is available
+          type: number
+        remaining_burst_capacity:
+          example: 3
+          title: remaining Burst Capacity
+          description: Current remaining burst capacity
+          type: integer
+      required:
+      - earliest_next_available
+      - remaining_burst_capacity
+    QuotaStats:
+      title: QuotaStats
+      description: Quota statistics, purely descriptive. Not used in Rate limiting decisions.
+      type: object
+      properties:
+        rpm:
+          example: 36
+          title: Rpm
+          description: Number of requests in last 60 seconds
+          type: integer
+      required:
+      - rpm
+    QuotaUpdate:
+      title: QuotaUpdate
+      description: Quota update schema
+      type: object
+      properties:
+        quota:
+          $ref: '#/components/schemas/Quota'
+        match:
+          $ref: '#/components/schemas/MatchRules'
+      required:
+      - quota
+      - match
+    CellDetails:
+      title: CellDetails
+      description: |
+        Information about current cell state and matched quotas.
+        Matched quotas are computed based on query.
+        Info and state are computed dynamically based on matched quotas.
+      type: object
+      properties:
+        quotas:
+          title: Quotas
+          description: Matched quotas
+          type: array
+          items:
+            description: Quota label
+            type: string
+        info:
+          $ref: '#/components/schemas/Quota'
+        state:
+          $ref: '#/components/schemas/QuotaState'
+      required:
+      - quotas
+      - info
+      - state
+    CellInfo:
+      title: CellInfo
+      description: |
+        Information about current cell state.
+        Info and state are computed dynamically based on matched quotas.
+      type: object
+      properties:
+        info:
+          $ref: '#/components/schemas/Quota'
+        state:
+          $ref: '#/components/schemas/QuotaState'
+      required:
+      - info
+      - state
+    UpdateResult:
+      title: UpdateResult
+      description: |
+        Result of the cell update. Allowed/Denied flag + cell info
+      type: object
+      properties:
+        allowed:
+          title: Allowed
+          description: |
+            Indicates if request was allowed
+            If request was allowed, rate limit state was already updated to accomodate
+            this request.
+            If request was rejected, rate limit was not updated
+          type: boolean
+        details:
+          $ref: '#/components/schemas/CellInfo'
+      required:
+      - allowed
+      - details
+    ProblemDetails:
+      description: Details of the error as described by RFC 7807
+      type: object
+      properties:
+        detail:
+          description: Explanation of this occurrence of the error
+          type: string
+        instance:
+          description: URI reference identifying this occurrence of the error
+          type: string
+        status:
+          type: integer
+        title:
+          description: Error message declared in the spec
+          type: string
+        type:
+          description: URI reference identifying the error
+          type: string
+      required:
+      - type
+      - title
+      - status
+      - detail
+";
+
+static DOCS_HTML: &str = include_str!("static/docs.html");
+
+async fn openapi() -> String {
+    DOCS_OPENAPI.to_string()
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::OK)
+        .content_type("text/html; charset=utf-8")
+        .body(DOCS_HTML)
+}
+
+async fn to_v1_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "v1/docs"))
+        .body("")
+}
+
+async fn to_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "docs"))
+        .body("")
+}
+
+// Tells that service is alive
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+///
+/// Each trait of the api service is implemented by its own type parameter
+pub fn make_scope<HealthImpl, QuotaImpl, RateLimitingImpl, S>(
+    prometheus: PrometheusMetrics,
+) -> actix_web::Scope
+where
+    HealthImpl: HealthApi<S> + 'static,
+    QuotaImpl: QuotaApi<S> + 'static,
+    RateLimitingImpl: RateLimitingApi<S> + 'static,
+    S: Send + Sync + 'static,
+{
+    use web::{delete, get, post};
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/cell/test", actix_web::http::Method::GET),
+                            ("/cell/update", actix_web::http::Method::POST),
+                            ("/v1/cell/test", actix_web::http::Method::GET),
+                            ("/v1/cell/update", actix_web::http::Method::POST),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/cell/test")
+                        .name("cell_test")
+                        .guard(guard::Get())
+                        .route(
+                            RateLimitingImpl::wrap_route(
+                                "cell_test",
+                                &["rate limiting"],
+                                get().to(RateLimitingImpl::cell_test),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/cell/update")
+                        .name("cell_update")
+                        .guard(guard::Post())
+                        .route(
+                            RateLimitingImpl::wrap_route(
+                                "cell_update",
+                                &["rate limiting"],
+                                post().to(RateLimitingImpl::cell_update),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/cell/test")
+                        .name("cell_test")
+                        .guard(guard::Get())
+                        .route(
+                            RateLimitingImpl::wrap_route(
+                                "cell_test",
+                                &["rate limiting"],
+                                get().to(RateLimitingImpl::cell_test),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/cell/update")
+                        .name("cell_update")
+                        .guard(guard::Post())
+                        .route(
+                            RateLimitingImpl::wrap_route(
+                                "cell_update",
+                                &["rate limiting"],
+                                post().to(RateLimitingImpl::cell_update),
+                            ),
+                        ),
+                );
+            RateLimitingImpl::configure_tag("rate limiting", scope, config);
+        })
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/health", actix_web::http::Method::GET),
+                            ("/v1/health", actix_web::http::Method::GET),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/health")
+                        .name("health")
+                        .guard(guard::Get())
+                        .route(
+                            HealthImpl::wrap_route(
+                                "health",
+                                &["health"],
+                                get().to(HealthImpl::health),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/health")
+                        .name("health")
+                        .guard(guard::Get())
+                        .route(
+                            HealthImpl::wrap_route(
+                                "health",
+                                &["health"],
+                                get().to(HealthImpl::health),
+                            ),
+                        ),
+                );
+            HealthImpl::configure_tag("health", scope, config);
+        })
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/quota", actix_web::http::Method::GET),
+                            ("/quota/{quota}", actix_web::http::Method::GET),
+                            ("/v1/quota", actix_web::http::Method::GET),
+                            ("/v1/quota/{quota}", actix_web::http::Method::GET),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/quota")
+                        .name("quota_list")
+                        .guard(guard::Get())
+                        .route(
+                            QuotaImpl::wrap_route(
+                                "quota_list",
+                                &["quota"],
+                                get().to(QuotaImpl::quota_list),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/quota/{quota}")
+                        .name("quota_details")
+                        .guard(guard::Get())
+                        .route(
+                            QuotaImpl::wrap_route(
+                                "quota_details",
+                                &["quota"],
+                                get().to(QuotaImpl::quota_details),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/quota")
+                        .name("quota_list")
+                        .guard(guard::Get())
+                        .route(
+                            QuotaImpl::wrap_route(
+                                "quota_list",
+                                &["quota"],
+                                get().to(QuotaImpl::quota_list),
+                            ),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/quota/{quota}")
+                        .name("quota_details")
+                        .guard(guard::Get())
+                        .route(
+                            QuotaImpl::wrap_route(
+                                "quota_details",
+                                &["quota"],
+                                get().to(QuotaImpl::quota_details),
+                            ),
+                        ),
+                );
+            QuotaImpl::configure_tag("quota", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        })
+        .wrap(prometheus);
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .service(api)
+}
+
+// Mock service
+// -------------------------------
+
+/// Implementation of `ApiService` with programmable responses, for use in tests
+pub mod mock {
+    use std::{collections::VecDeque, sync::Mutex};
+    use super::*;
+    type Responder<A, R> = Box<dyn FnMut(&A) -> R + Send>;
+    type Response<A, R> = Box<dyn FnOnce(&A) -> R + Send>;
+    /// Programmed responses and received arguments of a single operation
+    pub struct MockOperation<A, R> {
+        name: &'static str,
+        calls: Mutex<Vec<A>>,
+        responses: Mutex<VecDeque<Response<A, R>>>,
+        responder: Mutex<Option<Responder<A, R>>>,
+    }
+    impl<A: Clone, R> MockOperation<A, R> {
+        fn new(name: &'static str) -> Self {
+            MockOperation {
+                name,
+                calls: Mutex::new(Vec::new()),
+                responses: Mutex::new(VecDeque::new()),
+                responder: Mutex::new(None),
+            }
+        }
+        /// Respond to every call with the result of the closure
+        pub fn respond_with(&self, responder: impl FnMut(&A) -> R + Send + 'static) {
+            *self.responder.lock().unwrap() = Some(Box::new(responder));
+        }
+        /// Respond to the next call with the given response.
+        /// Queued responses are used before the closure set with `respond_with`
+        pub fn push_response(&self, response: R)
+        where
+            R: Send + 'static,
+        {
+            self.responses.lock().unwrap().push_back(Box::new(move |_| response));
+        }
+        /// Arguments of every call received so far
+        pub fn calls(&self) -> Vec<A> {
+            self.calls.lock().unwrap().clone()
+        }
+        pub fn call_count(&self) -> usize {
+            self.calls.lock().unwrap().len()
+        }
+        fn call(&self, args: A) -> R {
+            let queued = self.responses.lock().unwrap().pop_front();
+            let response = match queued {
+                Some(response) => response(&args),
+                None => {
+                    match self.responder.lock().unwrap().as_mut() {
+                        Some(responder) => responder(&args),
+                        None => {
+                            panic!("No response programmed for operation {}", self.name)
+                        }
+                    }
+                }
+            };
+            self.calls.lock().unwrap().push(args);
+            response
+        }
+    }
+    /// Arguments received by `health`
+    #[derive(Debug, Clone)]
+    pub struct HealthCall {}
+    /// Arguments received by `quota_list`
+    #[derive(Debug, Clone)]
+    pub struct QuotaListCall {}
+    /// Arguments received by `quota_details`
+    #[derive(Debug, Clone)]
+    pub struct QuotaDetailsCall {
+        pub path: QuotaDetailsPath,
+    }
+    /// Arguments received by `cell_test`
+    #[derive(Debug, Clone)]
+    pub struct CellTestCall {
+        pub query: CellTestQuery,
+    }
+    /// Arguments received by `cell_update`
+    #[derive(Debug, Clone)]
+    pub struct CellUpdateCall {
+        pub query: CellTestQuery,
+    }
+    /// Service whose operations respond with programmed responses.
+    /// Register it as app data and use as both service and state:
+    /// `make_scope::<MockApiService, ..., _>` in place of every type parameter
+    pub struct MockApiService {
+        pub health: MockOperation<HealthCall, web::Json<String>>,
+        pub quota_list: MockOperation<QuotaListCall, web::Json<Vec<String>>>,
+        pub quota_details: MockOperation<
+            QuotaDetailsCall,
+            Result<web::Json<QuotaDetails>, Detailed<QuotaDetailsError>>,
+        >,
+        pub cell_test: MockOperation<
+            CellTestCall,
+            Result<web::Json<CellDetails>, Detailed<CellTestError>>,
+        >,
+        pub cell_update: MockOperation<
+            CellUpdateCall,
+            Result<web::Json<UpdateResult>, Detailed<CellTestError>>,
+        >,
+    }
+    impl Default for MockApiService {
+        fn default() -> Self {
+            MockApiService {
+                health: MockOperation::new("health"),
+                quota_list: MockOperation::new("quota_list"),
+                quota_details: MockOperation::new("quota_details"),
+                cell_test: MockOperation::new("cell_test"),
+                cell_update: MockOperation::new("cell_update"),
+            }
+        }
+    }
+    #[async_trait(?Send)]
+    impl HealthApi<MockApiService> for MockApiService {
+        async fn health(data: web::Data<MockApiService>) -> web::Json<String> {
+            data.health.call(HealthCall {})
+        }
+    }
+    #[async_trait(?Send)]
+    impl QuotaApi<MockApiService> for MockApiService {
+        async fn quota_list(data: web::Data<MockApiService>) -> web::Json<Vec<String>> {
+            data.quota_list.call(QuotaListCall {})
+        }
+        async fn quota_details(
+            data: web::Data<MockApiService>,
+            path: web::Path<QuotaDetailsPath>,
+        ) -> Result<web::Json<QuotaDetails>, Detailed<QuotaDetailsError>> {
+            let path = path.into_inner();
+            data.quota_details.call(QuotaDetailsCall { path })
+        }
+    }
+    #[async_trait(?Send)]
+    impl RateLimitingApi<MockApiService> for MockApiService {
+        async fn cell_test(
+            data: web::Data<MockApiService>,
+            query: web::Query<CellTestQuery>,
+        ) -> Result<web::Json<CellDetails>, Detailed<CellTestError>> {
+            let query = query.into_inner();
+            data.cell_test.call(CellTestCall { query })
+        }
+        async fn cell_update(
+            data: web::Data<MockApiService>,
+            query: web::Query<CellTestQuery>,
+        ) -> Result<web::Json<UpdateResult>, Detailed<CellTestError>> {
+            let query = query.into_inner();
+            data.cell_update.call(CellUpdateCall { query })
+        }
+    }
+}
@@ -0,0 +1,55 @@
+#[async_trait(?Send)]
+impl<S> HealthApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// Check service health
+    async fn health(
+        _data: web::Data<S>,
+    ) -> web::Json<String> {
+        todo!()
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> QuotaApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// List quotas
+    async fn quota_list(
+        _data: web::Data<S>,
+    ) -> web::Json<Vec<String>> {
+        todo!()
+    }
+
+    /// Get quota details
+    async fn quota_details(
+        _data: web::Data<S>,
+        _path: web::Path<QuotaDetailsPath>,
+    ) -> Result<web::Json<QuotaDetails>, Detailed<QuotaDetailsError>> {
+        todo!()
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> RateLimitingApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// Get current rate limitation state for given query
+    async fn cell_test(
+        _data: web::Data<S>,
+        _query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<CellDetails>, Detailed<CellTestError>> {
+        todo!()
+    }
+
+    /// Try to accomodate for one request
+    async fn cell_update(
+        _data: web::Data<S>,
+        _query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<UpdateResult>, Detailed<CellTestError>> {
+        todo!()
+    }
+}
@@ -0,0 +1,13 @@
+#[async_trait(?Send)]
+impl<S> ApiService<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// Returns a greeting to the user!
+    async fn greet_user(
+        _data: web::Data<S>,
+        _path: web::Path<GreetUserPath>,
+    ) -> Result<web::Json<String>, Detailed<GreetUserError>> {
+        todo!()
+    }
+}
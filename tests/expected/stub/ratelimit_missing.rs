@@ -0,0 +1,15 @@
+    /// Get quota details
+    async fn quota_details(
+        _data: web::Data<S>,
+        _path: web::Path<QuotaDetailsPath>,
+    ) -> Result<web::Json<QuotaDetails>, Detailed<QuotaDetailsError>> {
+        todo!()
+    }
+
+    /// Try to accomodate for one request
+    async fn cell_update(
+        _data: web::Data<S>,
+        _query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<UpdateResult>, Detailed<CellTestError>> {
+        todo!()
+    }
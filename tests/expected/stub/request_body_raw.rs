@@ -0,0 +1,37 @@
+#[async_trait(?Send)]
+impl<S> ApiService<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// Upload large file without buffering it
+    async fn upload(
+        _data: web::Data<S>,
+        _body: web::Payload,
+    ) -> Result<web::Json<i64>, actix_web::Error> {
+        todo!()
+    }
+
+    /// Upload small image
+    async fn upload_thumbnail(
+        _data: web::Data<S>,
+        _body: web::Bytes,
+    ) -> web::Json<bool> {
+        todo!()
+    }
+
+    /// Add plain text note
+    async fn add_note(
+        _data: web::Data<S>,
+        _body: String,
+    ) -> web::Json<bool> {
+        todo!()
+    }
+
+    /// Ingest newline delimited events
+    async fn ingest_events(
+        _data: web::Data<S>,
+        _body: NdJson<Event>,
+    ) -> Result<web::Json<i64>, actix_web::Error> {
+        todo!()
+    }
+}
@@ -0,0 +1,110 @@
+#[async_trait(?Send)]
+impl<S> Authenticator<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn authenticate_bearer_auth(
+        _data: &web::Data<S>,
+        _credentials: &BearerAuth,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+
+    async fn authenticate_basic_auth(
+        _data: &web::Data<S>,
+        _credentials: &BasicAuth,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+
+    async fn authenticate_api_key(
+        _data: &web::Data<S>,
+        _credentials: &ApiKey,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+
+    async fn authenticate_query_key(
+        _data: &web::Data<S>,
+        _credentials: &QueryKey,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+
+    async fn authenticate_session(
+        _data: &web::Data<S>,
+        _credentials: &Session,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+
+    async fn authenticate_petstore_auth(
+        _data: &web::Data<S>,
+        _credentials: &PetstoreAuth,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        todo!()
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> ApiService<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    /// List pets visible to anyone, with more details for signed in users
+    async fn list_pets(
+        _data: web::Data<S>,
+        _auth: Option<Session>,
+    ) -> web::Json<Vec<String>> {
+        todo!()
+    }
+
+    /// Add pet
+    async fn add_pet(
+        _data: web::Data<S>,
+        _auth: PetstoreAuth,
+        _body: web::Json<String>,
+    ) -> web::Json<bool> {
+        todo!()
+    }
+
+    /// Get pet with the default security
+    async fn get_pet(
+        _data: web::Data<S>,
+        _auth: BearerAuth,
+        _path: web::Path<GetPetPath>,
+    ) -> web::Json<String> {
+        todo!()
+    }
+
+    /// Delete pet as admin or with an api key sent along with the token
+    async fn delete_pet(
+        _data: web::Data<S>,
+        _auth: DeletePetCredentials,
+        _path: web::Path<GetPetPath>,
+    ) -> web::Json<bool> {
+        todo!()
+    }
+
+    /// Public status
+    async fn status(
+        _data: web::Data<S>,
+        _query: web::Query<StatusQuery>,
+    ) -> web::Json<String> {
+        todo!()
+    }
+
+    /// Export with the query key
+    async fn export(
+        _data: web::Data<S>,
+        _auth: QueryKey,
+    ) -> web::Json<String> {
+        todo!()
+    }
+}
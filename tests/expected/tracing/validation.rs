@@ -0,0 +1,1050 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports, dead_code)]
+//! API auto-generated by apigen
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use actix_web::{
+    guard, http::StatusCode, middleware::{NormalizePath, TrailingSlash},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+
+use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
+
+use actix_web::{
+    body::MessageBody, dev::{Service, ServiceRequest, ServiceResponse, Transform},
+};
+
+use tracing::Instrument;
+
+use async_trait::async_trait;
+
+// Defaults
+// -------------------------------
+
+fn default_int_0() -> i64 {
+    0
+}
+
+fn default_int_10() -> i64 {
+    10
+}
+
+// Enums
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum AddShapeBody {
+    #[serde(rename = "square")]
+    Square(Square),
+    #[serde(rename = "label")]
+    Label(Label),
+}
+
+// Struct
+// -------------------------------
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpdateUserPath {
+    /// Identifier of the user
+    pub user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpdateUserBodyAddress {
+    pub city: String,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpdateUserBody {
+    pub name: String,
+    pub email: String,
+    pub birthday: Option<String>,
+    #[serde(default = "default_int_0")]
+    pub score: i64,
+    pub tags: Vec<String>,
+    pub address: UpdateUserBodyAddress,
+    pub previous: Option<Vec<UpdateUserBodyAddress>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ListUsersQuery {
+    #[serde(default = "default_int_10")]
+    pub limit: i64,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Square {
+    pub side: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub text: String,
+}
+
+// Request errors
+// -------------------------------
+
+/// Invalid value of the request argument
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the invalid value like `body.items[0].name`
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Request arguments that could not be parsed or violate constraints declared in the spec.
+/// Responds with 422 Unprocessable Entity listing every invalid value
+#[derive(Debug, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn check(errors: Vec<FieldError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors { errors }),
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.reason))
+            .collect();
+        write!(f, "Request is invalid: {}", errors.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Field the deserialization error refers to, like ``missing field `name` ``
+fn error_field(source: &str, reason: &str) -> String {
+    let field = reason
+        .split_once("field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field);
+    match field {
+        Some(field) => format!("{source}.{field}"),
+        None => source.to_string(),
+    }
+}
+
+fn request_error(source: &str, reason: String) -> actix_web::Error {
+    let field = error_field(source, &reason);
+    ValidationErrors {
+        errors: vec![FieldError::new(& field, reason)],
+    }
+        .into()
+}
+
+fn json_error(
+    err: actix_web::error::JsonPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::JsonPayloadError::Deserialize(err) => {
+            request_error("body", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn query_error(
+    err: actix_web::error::QueryPayloadError,
+    _req: &HttpRequest,
+) -> actix_web::Error {
+    match err {
+        actix_web::error::QueryPayloadError::Deserialize(err) => {
+            request_error("query", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+fn path_error(err: actix_web::error::PathError, _req: &HttpRequest) -> actix_web::Error {
+    match err {
+        actix_web::error::PathError::Deserialize(err) => {
+            request_error("path", err.to_string())
+        }
+        err => err.into(),
+    }
+}
+
+// Validation
+// -------------------------------
+
+/// Value that is checked against constraints declared in the spec
+pub trait Validate {
+    /// Collect constraint violations of the value located at `path`
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>);
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        self.validate_into("", &mut errors);
+        ValidationErrors::check(errors)
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        if let Some(value) = self {
+            value.validate_into(path, errors)
+        }
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        for (index, value) in self.iter().enumerate() {
+            value.validate_into(&format!("{path}[{index}]"), errors)
+        }
+    }
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    match path.is_empty() {
+        true => field.to_string(),
+        false => format!("{path}.{field}"),
+    }
+}
+
+fn check_length(
+    path: &str,
+    value: &str,
+    min: Option<usize>,
+    max: Option<usize>,
+    errors: &mut Vec<FieldError>,
+) {
+    let length = value.chars().count();
+    if let Some(min) = min.filter(|min| length < *min) {
+        errors
+            .push(
+                FieldError::new(path, format!("must be at least {min} characters long")),
+            );
+    }
+    if let Some(max) = max.filter(|max| length > *max) {
+        errors
+            .push(
+                FieldError::new(path, format!("must be at most {max} characters long")),
+            );
+    }
+}
+
+fn check_pattern(
+    path: &str,
+    value: &str,
+    regex: &std::sync::OnceLock<regex::Regex>,
+    pattern: &str,
+    errors: &mut Vec<FieldError>,
+) {
+    let regex = regex
+        .get_or_init(|| {
+            regex::Regex::new(pattern)
+                .expect("Pattern in the spec must be a valid regular expression")
+        });
+    if !regex.is_match(value) {
+        errors.push(FieldError::new(path, format!("must match pattern {pattern}")));
+    }
+}
+
+fn check_range<T: PartialOrd + Display + Copy>(
+    path: &str,
+    value: T,
+    min: Option<(T, bool)>,
+    max: Option<(T, bool)>,
+    errors: &mut Vec<FieldError>,
+) {
+    if let Some((min, exclusive)) = min {
+        if value < min || (exclusive && value == min) {
+            let relation = match exclusive {
+                true => "greater than",
+                false => "greater than or equal to",
+            };
+            errors.push(FieldError::new(path, format!("must be {relation} {min}")));
+        }
+    }
+    if let Some((max, exclusive)) = max {
+        if value > max || (exclusive && value == max) {
+            let relation = match exclusive {
+                true => "less than",
+                false => "less than or equal to",
+            };
+            errors.push(FieldError::new(path, format!("must be {relation} {max}")));
+        }
+    }
+}
+
+fn check_multiple_of_int(
+    path: &str,
+    value: i64,
+    divisor: i64,
+    errors: &mut Vec<FieldError>,
+) {
+    if divisor != 0 && value % divisor != 0 {
+        errors.push(FieldError::new(path, format!("must be a multiple of {divisor}")));
+    }
+}
+
+fn check_multiple_of_float(
+    path: &str,
+    value: f64,
+    divisor: f64,
+    errors: &mut Vec<FieldError>,
+) {
+    let quotient = value / divisor;
+    if (quotient - quotient.round()).abs() > 1e-9 {
+        errors.push(FieldError::new(path, format!("must be a multiple of {divisor}")));
+    }
+}
+
+fn check_items<T: PartialEq>(
+    path: &str,
+    value: &[T],
+    min: Option<usize>,
+    max: Option<usize>,
+    unique: bool,
+    errors: &mut Vec<FieldError>,
+) {
+    if let Some(min) = min.filter(|min| value.len() < *min) {
+        errors.push(FieldError::new(path, format!("must contain at least {min} items")));
+    }
+    if let Some(max) = max.filter(|max| value.len() > *max) {
+        errors.push(FieldError::new(path, format!("must contain at most {max} items")));
+    }
+    if unique && (1..value.len()).any(|index| value[..index].contains(&value[index])) {
+        errors.push(FieldError::new(path, "must contain unique items"));
+    }
+}
+
+fn check_format(path: &str, value: &str, format: &str, errors: &mut Vec<FieldError>) {
+    let valid = match format {
+        "date" => is_date(value),
+        "date-time" => is_date_time(value),
+        "email" => is_email(value),
+        "uuid" => is_uuid(value),
+        "ipv4" => value.parse::<std::net::Ipv4Addr>().is_ok(),
+        "ipv6" => value.parse::<std::net::Ipv6Addr>().is_ok(),
+        "uri" => is_uri(value),
+        _ => true,
+    };
+    if !valid {
+        errors.push(FieldError::new(path, format!("must be a valid {format}")));
+    }
+}
+
+fn digits(value: &str) -> Option<u32> {
+    match !value.is_empty() && value.bytes().all(|x| x.is_ascii_digit()) {
+        true => value.parse().ok(),
+        false => None,
+    }
+}
+
+/// Full date as defined by RFC 3339, like `2023-01-31`
+fn is_date(value: &str) -> bool {
+    let (Some(_year), Some(month), Some(day)) = (
+        value.get(0..4).and_then(digits),
+        value.get(5..7).and_then(digits),
+        value.get(8..10).and_then(digits),
+    ) else {
+        return false;
+    };
+    value.len() == 10 && value.get(4..5) == Some("-") && value.get(7..8) == Some("-")
+        && (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+/// Full time as defined by RFC 3339, like `23:59:59.999+02:00`
+fn is_time(value: &str) -> bool {
+    let (Some(hour), Some(minute), Some(second)) = (
+        value.get(0..2).and_then(digits),
+        value.get(3..5).and_then(digits),
+        value.get(6..8).and_then(digits),
+    ) else {
+        return false;
+    };
+    if value.get(2..3) != Some(":") || value.get(5..6) != Some(":") {
+        return false;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+    let mut offset = &value[8..];
+    if let Some(fraction) = offset.strip_prefix('.') {
+        let length = fraction.bytes().take_while(|x| x.is_ascii_digit()).count();
+        if length == 0 {
+            return false;
+        }
+        offset = &fraction[length..];
+    }
+    match offset {
+        "Z" | "z" => true,
+        offset => {
+            offset.len() == 6 && matches!(offset.get(0..1), Some("+" | "-"))
+                && offset.get(1..3).and_then(digits).is_some_and(|x| x <= 23)
+                && offset.get(3..4) == Some(":")
+                && offset.get(4..6).and_then(digits).is_some_and(|x| x <= 59)
+        }
+    }
+}
+
+/// Date and time as defined by RFC 3339, like `2023-01-31T23:59:59Z`
+fn is_date_time(value: &str) -> bool {
+    value.get(0..10).is_some_and(is_date) && matches!(value.get(10..11), Some("T" | "t"))
+        && value.get(11..).is_some_and(is_time)
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value
+            .bytes()
+            .enumerate()
+            .all(|(index, x)| match index {
+                8 | 13 | 18 | 23 => x == b'-',
+                _ => x.is_ascii_hexdigit(),
+            })
+}
+
+fn is_uri(value: &str) -> bool {
+    let Some((scheme, _)) = value.split_once(':') else {
+        return false;
+    };
+    scheme.starts_with(|x: char| x.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || matches!(x, '+' | '-' | '.'))
+        && !value.chars().any(char::is_whitespace)
+}
+
+impl Validate for UpdateUserPath {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "user_id");
+            let value = &self.user_id;
+            check_format(&path, value, "uuid", errors);
+        }
+    }
+}
+
+impl Validate for UpdateUserBodyAddress {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "city");
+            let value = &self.city;
+            check_length(&path, value, Some(2), None, errors);
+        }
+        {
+            let path = field_path(path, "updated_at");
+            let value = &self.updated_at;
+            if let Some(value) = value {
+                check_format(&path, value, "date-time", errors);
+            }
+        }
+    }
+}
+
+impl Validate for UpdateUserBody {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "name");
+            let value = &self.name;
+            check_length(&path, value, Some(1), Some(32), errors);
+        }
+        {
+            let path = field_path(path, "email");
+            let value = &self.email;
+            check_format(&path, value, "email", errors);
+        }
+        {
+            let path = field_path(path, "birthday");
+            let value = &self.birthday;
+            if let Some(value) = value {
+                check_format(&path, value, "date", errors);
+            }
+        }
+        {
+            let path = field_path(path, "score");
+            let value = &self.score;
+            check_multiple_of_int(&path, *value, 5, errors);
+        }
+        {
+            let path = field_path(path, "tags");
+            let value = &self.tags;
+            check_items(&path, value, Some(1), None, true, errors);
+            for (index, value) in value.iter().enumerate() {
+                let path = format!("{path}[{index}]");
+                check_length(&path, value, None, Some(8), errors);
+            }
+        }
+        {
+            let path = field_path(path, "address");
+            let value = &self.address;
+            value.validate_into(&path, errors);
+        }
+        {
+            let path = field_path(path, "previous");
+            let value = &self.previous;
+            value.validate_into(&path, errors);
+        }
+    }
+}
+
+impl Validate for ListUsersQuery {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "limit");
+            let value = &self.limit;
+            check_range(&path, *value, Some((1, false)), Some((100, false)), errors);
+        }
+        {
+            let path = field_path(path, "name");
+            let value = &self.name;
+            if let Some(value) = value {
+                static PATTERN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+                check_pattern(&path, value, &PATTERN, "^[a-z]+$", errors);
+            }
+        }
+    }
+}
+
+impl Validate for Square {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        {
+            let path = field_path(path, "side");
+            let value = &self.side;
+            check_range(&path, *value, Some((0, true)), None, errors);
+        }
+    }
+}
+
+impl Validate for AddShapeBody {
+    fn validate_into(&self, path: &str, errors: &mut Vec<FieldError>) {
+        match self {
+            Self::Square(value) => value.validate_into(path, errors),
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+    }
+}
+
+// Error with details
+// -------------------------------
+
+/// Create detailed errors with ease
+#[macro_export]
+macro_rules! detailed {
+    ($err:expr,$msg:expr) => {
+        $crate::server::api::Detailed { error : $err, details : $msg .to_string(),
+        instance : None, }
+    };
+}
+
+/// Bails with detailed api error
+#[macro_export]
+macro_rules! apibail {
+    ($err:expr,$msg:expr) => {
+        return Err($crate::server::api::Detailed { error : $err, details : $msg
+        .to_string(), instance : None, })
+    };
+}
+
+pub trait StatusCoded {
+    fn status_code(&self) -> StatusCode;
+    /// URI reference identifying the error in problem details
+    fn problem_type(&self) -> &'static str {
+        "about:blank"
+    }
+}
+
+#[derive(Debug)]
+pub struct Detailed<E> {
+    pub error: E,
+    pub details: String,
+    /// URI reference identifying this occurrence of the error
+    pub instance: Option<String>,
+}
+
+impl<E> Detailed<E> {
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+/// Body of the error response as described by RFC 7807
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl<E: Display> Display for Detailed<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. Reason: {}", self.error, self.details)
+    }
+}
+
+impl<E: Display + Debug> std::error::Error for Detailed<E> {}
+
+impl<E: Display + Debug> ResponseError for Detailed<E>
+where
+    E: StatusCoded,
+{
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        let mut response = HttpResponse::build(status)
+            .content_type("application/problem+json")
+            .json(ProblemDetails {
+                type_: self.error.problem_type().to_string(),
+                title: self.error.to_string(),
+                status: status.as_u16(),
+                detail: self.details.clone(),
+                instance: self.instance.clone(),
+            });
+        response.extensions_mut().insert(ErrorType(format!("{:?}", self.error)));
+        response
+    }
+}
+
+/// Converts some result to Result with detailed api error
+pub trait ApiErr<T, E> {
+    /// Wrap the error value with additional context.
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static;
+}
+
+impl<T, E> ApiErr<T, E> for Result<T, E>
+where
+    E: Debug + Send + Sync + 'static,
+{
+    fn apierr<C>(self, err: C) -> Result<T, Detailed<C>>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(original_error) => {
+                Err(Detailed {
+                    error: err,
+                    details: format!("{:?}", original_error),
+                    instance: None,
+                })
+            }
+        }
+    }
+}
+
+// Error
+// -------------------------------
+
+// Api service
+// -------------------------------
+
+#[async_trait(?Send)]
+pub trait ApiService<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Update user profile
+    async fn update_user(
+        data: web::Data<S>,
+        path: web::Path<UpdateUserPath>,
+        body: web::Json<UpdateUserBody>,
+    ) -> web::Json<bool>;
+    /// List users
+    async fn list_users(
+        data: web::Data<S>,
+        query: web::Query<ListUsersQuery>,
+    ) -> web::Json<Vec<String>>;
+    /// Add shape
+    async fn add_shape(
+        data: web::Data<S>,
+        body: web::Json<AddShapeBody>,
+    ) -> web::Json<bool>;
+    /// Hook wrapping the route of the operation before it is added to the scope,
+    /// e.g. `route.wrap(RateLimiter::default())` for some of the tags or operations
+    fn wrap_route(
+        _operation_id: &str,
+        _tags: &[&str],
+        route: actix_web::Route,
+    ) -> actix_web::Route {
+        route
+    }
+    /// Hook adding the scope with routes of the operations of the tag, the first of their tags,
+    /// e.g. `config.service(scope.wrap(Cors::default()))` for middlewares of the whole tag
+    fn configure_tag(
+        _tag: &str,
+        scope: actix_web::Scope,
+        config: &mut web::ServiceConfig,
+    ) {
+        config.service(scope);
+    }
+}
+
+// Run service function (+ helper functions)
+// -----------------------------------------
+
+static DOCS_OPENAPI: &str = include_str!("static/openapi.yaml");
+
+static DOCS_HTML: &str = include_str!("static/docs.html");
+
+async fn openapi() -> String {
+    DOCS_OPENAPI.to_string()
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::OK)
+        .content_type("text/html; charset=utf-8")
+        .body(DOCS_HTML)
+}
+
+async fn to_v1_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "v1/docs"))
+        .body("")
+}
+
+async fn to_docs() -> HttpResponse {
+    HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
+        .append_header(("Location", "docs"))
+        .body("")
+}
+
+// Tells that service is alive
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// Check constraints of `update_user` arguments before calling the service
+async fn checked_update_user<T, S>(
+    data: web::Data<S>,
+    path: web::Path<UpdateUserPath>,
+    body: web::Json<UpdateUserBody>,
+) -> Result<web::Json<bool>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let mut errors = Vec::new();
+    path.validate_into("path", &mut errors);
+    body.validate_into("body", &mut errors);
+    ValidationErrors::check(errors)?;
+    Ok(T::update_user(data, path, body).await)
+}
+
+/// Check constraints of `list_users` arguments before calling the service
+async fn checked_list_users<T, S>(
+    data: web::Data<S>,
+    query: web::Query<ListUsersQuery>,
+) -> Result<web::Json<Vec<String>>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let mut errors = Vec::new();
+    query.validate_into("query", &mut errors);
+    ValidationErrors::check(errors)?;
+    Ok(T::list_users(data, query).await)
+}
+
+/// Check constraints of `add_shape` arguments before calling the service
+async fn checked_add_shape<T, S>(
+    data: web::Data<S>,
+    body: web::Json<AddShapeBody>,
+) -> Result<web::Json<bool>, actix_web::Error>
+where
+    T: ApiService<S>,
+    S: Send + Sync + 'static,
+{
+    let mut errors = Vec::new();
+    body.validate_into("body", &mut errors);
+    ValidationErrors::check(errors)?;
+    Ok(T::add_shape(data, body).await)
+}
+
+// Tracing
+// -------------------------------
+
+/// Variant of the error responded by the operation, recorded as `error.type` of its span
+#[derive(Debug, Clone)]
+pub struct ErrorType(pub String);
+
+/// Route middleware handling the request of the operation inside of its span
+#[derive(Clone, Copy)]
+pub struct OperationSpan(pub fn(&ServiceRequest) -> tracing::Span);
+
+/// Span named by the `operationId`, with the fields recorded by `OperationSpan`
+macro_rules! operation_span {
+    ($operation_id:literal, $route:literal) => {
+        OperationSpan(| req | { tracing::info_span!($operation_id, http.request.method =
+        % req.method(), http.route = $route, http.response.status_code =
+        tracing::field::Empty, error. type = tracing::field::Empty,) })
+    };
+}
+
+impl<S, B> Transform<S, ServiceRequest> for OperationSpan
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = OperationSpanService<S>;
+    type InitError = ();
+    type Future = std::future::Ready<Result<Self::Transform, Self::InitError>>;
+    fn new_transform(&self, service: S) -> Self::Future {
+        std::future::ready(
+            Ok(OperationSpanService {
+                service,
+                span: self.0,
+            }),
+        )
+    }
+}
+
+pub struct OperationSpanService<S> {
+    service: S,
+    span: fn(&ServiceRequest) -> tracing::Span,
+}
+
+impl<S, B> Service<ServiceRequest> for OperationSpanService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>,
+    >;
+    actix_web::dev::forward_ready!(service);
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let span = (self.span)(&req);
+        let future = span.in_scope(|| self.service.call(req));
+        let instrumented = span.clone();
+        Box::pin(
+            async move {
+                let result = future.await;
+                match &result {
+                    Ok(response) => {
+                        span.record(
+                            "http.response.status_code",
+                            response.status().as_u16(),
+                        );
+                        if let Some(ErrorType(error)) = response
+                            .response()
+                            .extensions()
+                            .get()
+                        {
+                            span.record("error.type", error.as_str());
+                        }
+                    }
+                    Err(error) => {
+                        let status = error.as_response_error().status_code();
+                        span.record("http.response.status_code", status.as_u16());
+                    }
+                }
+                result
+            }
+                .instrument(instrumented),
+        )
+    }
+}
+
+/// Path of the request left to match by the routes of `make_scope`
+struct ScopePath(String);
+
+/// Guard of the scope of a tag, passing only requests of its routes,
+/// so that the scopes of the other tags get the rest
+fn tag_guard(routes: &[(&str, actix_web::http::Method)]) -> impl guard::Guard {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, method)| (actix_web::dev::ResourceDef::new(*path), method.clone()))
+        .collect();
+    guard::fn_guard(move |ctx| {
+        let extensions = ctx.req_data();
+        let Some(ScopePath(path)) = extensions.get::<ScopePath>() else {
+            return false;
+        };
+        routes
+            .iter()
+            .any(|(resource, method)| {
+                ctx.head().method == *method && resource.is_match(path)
+            })
+    })
+}
+
+/// Just make scope - can be used for manual server creation
+/// And to combine with other scopes
+///
+/// Every operation is a resource named by its `operationId`, so middlewares
+/// label requests with `match_name()` and `match_pattern()` of the spec path.
+/// Routes are grouped into scopes by the first tag of the operations,
+/// which `configure_tag` hook adds to the app
+pub fn make_scope<T, S>(prometheus: PrometheusMetrics) -> actix_web::Scope
+where
+    T: ApiService<S> + 'static,
+    S: Send + Sync + 'static,
+{
+    use web::{delete, get, post};
+    let api = web::scope("")
+        .app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .configure(|config| {
+            let scope = web::scope("")
+                .guard(
+                    tag_guard(
+                        &[
+                            ("/shapes", actix_web::http::Method::POST),
+                            ("/users", actix_web::http::Method::GET),
+                            ("/users/{user_id}", actix_web::http::Method::POST),
+                            ("/v1/shapes", actix_web::http::Method::POST),
+                            ("/v1/users", actix_web::http::Method::GET),
+                            ("/v1/users/{user_id}", actix_web::http::Method::POST),
+                        ],
+                    ),
+                )
+                .service(
+                    web::resource("/shapes")
+                        .name("add_shape")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "add_shape",
+                                    &[],
+                                    post().to(checked_add_shape::<T, S>),
+                                )
+                                .wrap(operation_span!("add_shape", "/shapes")),
+                        ),
+                )
+                .service(
+                    web::resource("/users")
+                        .name("list_users")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "list_users",
+                                    &[],
+                                    get().to(checked_list_users::<T, S>),
+                                )
+                                .wrap(operation_span!("list_users", "/users")),
+                        ),
+                )
+                .service(
+                    web::resource("/users/{user_id}")
+                        .name("update_user")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "update_user",
+                                    &[],
+                                    post().to(checked_update_user::<T, S>),
+                                )
+                                .wrap(operation_span!("update_user", "/users/{user_id}")),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/shapes")
+                        .name("add_shape")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "add_shape",
+                                    &[],
+                                    post().to(checked_add_shape::<T, S>),
+                                )
+                                .wrap(operation_span!("add_shape", "/v1/shapes")),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/users")
+                        .name("list_users")
+                        .guard(guard::Get())
+                        .route(
+                            T::wrap_route(
+                                    "list_users",
+                                    &[],
+                                    get().to(checked_list_users::<T, S>),
+                                )
+                                .wrap(operation_span!("list_users", "/v1/users")),
+                        ),
+                )
+                .service(
+                    web::resource("/v1/users/{user_id}")
+                        .name("update_user")
+                        .guard(guard::Post())
+                        .route(
+                            T::wrap_route(
+                                    "update_user",
+                                    &[],
+                                    post().to(checked_update_user::<T, S>),
+                                )
+                                .wrap(operation_span!("update_user", "/v1/users/{user_id}")),
+                        ),
+                );
+            T::configure_tag("default", scope, config);
+        })
+        .wrap_fn(|req, service| {
+            let path = req.match_info().unprocessed().to_string();
+            actix_web::HttpMessage::extensions_mut(&req).insert(ScopePath(path));
+            actix_web::dev::Service::call(service, req)
+        })
+        .wrap(prometheus);
+    web::scope("")
+        .route("/health", get().to(health))
+        .route("/", get().to(to_docs))
+        .route("/docs", get().to(docs))
+        .route("/openapi.yaml", get().to(openapi))
+        .route("/v1", get().to(to_v1_docs))
+        .route("/v1/", get().to(to_docs))
+        .route("/v1/docs", get().to(docs))
+        .route("/v1/openapi.yaml", get().to(openapi))
+        .service(api)
+}
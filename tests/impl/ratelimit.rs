@@ -0,0 +1,53 @@
+struct Server;
+
+#[async_trait(?Send)]
+impl<S> ApiService<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn health(_data: web::Data<S>) -> web::Json<String> {
+        web::Json("ok".to_string())
+    }
+
+    async fn quota_list(_data: web::Data<S>) -> web::Json<Vec<String>> {
+        web::Json(Vec::new())
+    }
+
+    async fn cell_test(
+        _data: web::Data<S>,
+        _query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<CellDetails>, Detailed<CellTestError>> {
+        todo!()
+    }
+}
+
+// TODO: fn cell_update(data, body) once the cells are stored
+fn quota_details_cache() {}
+
+fn quota_details() {}
+
+impl Server {
+    fn cell_update(&self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Display for Server {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "quota_details")
+    }
+}
+
+mod legacy {
+    struct OldServer;
+
+    #[async_trait(?Send)]
+    impl<S> ApiService<S> for OldServer
+    where
+        S: Send + Sync + 'static,
+    {
+        async fn quota_details(_data: web::Data<S>) -> web::Json<String> {
+            todo!()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+struct Server;
+
+#[async_trait(?Send)]
+impl<S> HealthApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn health(_data: web::Data<S>) -> web::Json<String> {
+        web::Json("ok".to_string())
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> QuotaApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn quota_list(_data: web::Data<S>) -> web::Json<Vec<String>> {
+        web::Json(Vec::new())
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> RateLimitingApi<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn cell_test(
+        _data: web::Data<S>,
+        _query: web::Query<CellTestQuery>,
+    ) -> Result<web::Json<CellDetails>, Detailed<CellTestError>> {
+        todo!()
+    }
+}
+
+impl Server {
+    fn quota_details(&self) {}
+}
@@ -0,0 +1,28 @@
+struct Server;
+
+#[async_trait(?Send)]
+impl<S> Authenticator<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn authenticate_bearer_auth(
+        _data: &web::Data<S>,
+        credentials: &BearerAuth,
+        _scopes: &[&str],
+    ) -> Result<(), AuthError> {
+        match credentials.token.as_str() {
+            "secret" => Ok(()),
+            _ => Err(AuthError::Unauthorized("Invalid token".to_string())),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> ApiService<S> for Server
+where
+    S: Send + Sync + 'static,
+{
+    async fn list_pets(_data: web::Data<S>, _auth: Option<Session>) -> web::Json<Vec<String>> {
+        web::Json(Vec::new())
+    }
+}
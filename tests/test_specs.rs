@@ -1,11 +1,12 @@
 use std::{
     fs::{read_to_string, File},
     io::Write,
+    path::Path,
 };
 
 use anyhow::Result;
 
-use cargo_actix_openapi::OpenapiWithMeta;
+use cargo_actix_openapi::{ApiOptions, Extractor, Metrics, OpenapiWithMeta};
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 
@@ -40,6 +41,29 @@ fn compare(got: &String, expected_path: &String) {
     assert_eq!(expected, got);
 }
 
+/// Spec of the test case, served by the api as `static/openapi.yaml`
+fn read_spec(case_name: &str) -> Result<Vec<OpenapiWithMeta>> {
+    Ok(vec![OpenapiWithMeta {
+        content: read_to_string(format!("tests/openapi/{case_name}.yaml"))?,
+        path: "static/openapi.yaml".to_string(),
+    }])
+}
+
+/// Generate api of the spec with the options and compare it
+/// with `tests/expected/{expected_dir}/{case_name}.rs`
+fn check_expected(case_name: &str, options: &ApiOptions, expected_dir: &str) -> Result<()> {
+    let specs = read_spec(case_name)?;
+
+    let (_, got) = cargo_actix_openapi::generate_api("static/docs.html", &specs, options)?;
+
+    compare(
+        &got,
+        &format!("tests/expected/{expected_dir}/{case_name}.rs"),
+    );
+
+    Ok(())
+}
+
 #[rstest]
 #[case("helloworld")]
 #[case("request_body")]
@@ -50,20 +74,23 @@ fn compare(got: &String, expected_path: &String) {
 #[case("reference")]
 #[case("ratelimit")]
 #[case("anyof")]
+#[case("request_body_media")]
+#[case("request_body_raw")]
+#[case("event_stream")]
+#[case("validation")]
+#[case("validation_body")]
+#[case("response_range")]
+#[case("security")]
+#[case("security_unused")]
+#[case("response_text")]
 fn test_specs(#[case] case_name: &str) -> Result<()> {
-    let filename = format!("tests/openapi/{case_name}.yaml");
-    let expected_filename = format!("tests/expected/{case_name}.rs");
-    let expected_model = format!("tests/expected/{case_name}.yaml");
+    let specs = read_spec(case_name)?;
 
-    let specs = vec![OpenapiWithMeta {
-        content: read_to_string(filename)?,
-        path: "static/openapi.yaml".to_string(),
-    }];
+    let (got_model, got) =
+        cargo_actix_openapi::generate_api("static/docs.html", &specs, &ApiOptions::default())?;
 
-    let (got_model, got) = cargo_actix_openapi::generate_api("static/docs.html", &specs)?;
-
-    compare(&got, &expected_filename);
-    compare(&got_model, &expected_model);
+    compare(&got, &format!("tests/expected/{case_name}.rs"));
+    compare(&got_model, &format!("tests/expected/{case_name}.yaml"));
 
     Ok(())
 }
@@ -86,10 +113,287 @@ fn test_multi(#[case] case_name: &str) -> Result<()> {
         path: "static/openapi_v2.yaml".to_string(),
     });
 
-    let (got_model, got) = cargo_actix_openapi::generate_api("static/docs.html", &specs)?;
+    let (got_model, got) =
+        cargo_actix_openapi::generate_api("static/docs.html", &specs, &ApiOptions::default())?;
 
     compare(&got, &expected_filename);
     compare(&got_model, &expected_model);
 
     Ok(())
 }
+
+#[rstest]
+#[case("error")]
+#[case("request_body")]
+#[case("request_body_media")]
+#[case("request_body_raw")]
+#[case("validation")]
+#[case("security")]
+fn test_mock(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        mock: true,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "mock")
+}
+
+#[rstest]
+#[case("validation")]
+fn test_document_errors(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        document_errors: true,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "document_errors")
+}
+
+#[rstest]
+#[case("helloworld", Metrics::None)]
+#[case("security", Metrics::Middleware)]
+fn test_metrics(#[case] case_name: &str, #[case] metrics: Metrics) -> Result<()> {
+    let options = ApiOptions {
+        metrics,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "metrics")
+}
+
+#[rstest]
+#[case("error")]
+#[case("validation")]
+fn test_tracing(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        tracing: true,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "tracing")
+}
+
+#[rstest]
+#[case("error")]
+#[case("security")]
+fn test_extractors(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        mock: true,
+        extractors: vec![
+            "HttpRequest".parse::<Extractor>()?,
+            "user=web::ReqData<String>".parse::<Extractor>()?,
+        ],
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "extractors")
+}
+
+#[rstest]
+#[case("ratelimit")]
+#[case("security")]
+#[case("tags")]
+fn test_split_by_tag(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        mock: true,
+        split_by_tag: true,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "split_by_tag")
+}
+
+#[rstest]
+#[case("helloworld")]
+#[case("request_body_nested")]
+#[case("default_parameter")]
+#[case("error")]
+#[case("enum")]
+#[case("request_body_media")]
+#[case("request_body_raw")]
+#[case("event_stream")]
+#[case("response_text")]
+fn test_client(#[case] case_name: &str) -> Result<()> {
+    let got = cargo_actix_openapi::generate_client(&read_spec(case_name)?, &ApiOptions::default())?;
+
+    compare(&got, &format!("tests/expected/client/{case_name}.rs"));
+
+    Ok(())
+}
+
+/// Models of the client are derived and rendered the same way as the ones of the api
+#[rstest]
+#[case("error")]
+#[case("request_body_nested")]
+fn test_client_options(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        derives: vec!["Hash".to_string(), "PartialOrd".to_string()],
+        templates: Some("tests/templates".into()),
+        ..Default::default()
+    };
+
+    let got = cargo_actix_openapi::generate_client(&read_spec(case_name)?, &options)?;
+
+    compare(
+        &got,
+        &format!("tests/expected/client/options/{case_name}.rs"),
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("error")]
+#[case("ratelimit")]
+#[case("request_body_raw")]
+#[case("security")]
+fn test_stub(#[case] case_name: &str) -> Result<()> {
+    let got = cargo_actix_openapi::generate_stub(
+        &read_spec(case_name)?,
+        "Server",
+        None,
+        &ApiOptions::default(),
+    )?;
+
+    compare(&got, &format!("tests/expected/stub/{case_name}.rs"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("ratelimit")]
+#[case("security")]
+fn test_stub_missing(#[case] case_name: &str) -> Result<()> {
+    let implementation = read_to_string(format!("tests/impl/{case_name}.rs"))?;
+
+    let got = cargo_actix_openapi::generate_stub(
+        &read_spec(case_name)?,
+        "Server",
+        Some(&implementation),
+        &ApiOptions::default(),
+    )?;
+
+    compare(&got, &format!("tests/expected/stub/{case_name}_missing.rs"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("ratelimit", false)]
+#[case("ratelimit", true)]
+fn test_stub_split_by_tag(#[case] case_name: &str, #[case] missing: bool) -> Result<()> {
+    let expected_filename = match missing {
+        true => format!("tests/expected/split_by_tag/stub_{case_name}_missing.rs"),
+        false => format!("tests/expected/split_by_tag/stub_{case_name}.rs"),
+    };
+
+    let implementation = match missing {
+        true => Some(read_to_string(format!(
+            "tests/impl/{case_name}_split_by_tag.rs"
+        ))?),
+        false => None,
+    };
+
+    let options = ApiOptions {
+        split_by_tag: true,
+        ..Default::default()
+    };
+
+    let got = cargo_actix_openapi::generate_stub(
+        &read_spec(case_name)?,
+        "Server",
+        implementation.as_deref(),
+        &options,
+    )?;
+
+    compare(&got, &expected_filename);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("src/api.rs", "api")]
+#[case("src/lib.rs", "crate_root")]
+fn test_module_path(#[case] out_path: &str, #[case] expected_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        module_path: cargo_actix_openapi::module_path(Path::new(out_path)),
+        ..Default::default()
+    };
+
+    check_expected("error", &options, &format!("module_path/{expected_name}"))
+}
+
+#[rstest]
+#[case("helloworld")]
+fn test_included(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        included: true,
+        docs_html: Some(cargo_actix_openapi::generate_docs("Hello")?),
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "included")
+}
+
+#[rstest]
+#[case("enum")]
+#[case("error")]
+fn test_derives(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        derives: vec!["Hash".to_string(), "PartialOrd".to_string()],
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "derives")
+}
+
+#[rstest]
+#[case("validation")]
+fn test_type_map(#[case] case_name: &str) -> Result<()> {
+    let mappings = [
+        "uuid=uuid::Uuid",
+        "date=chrono::NaiveDate",
+        "date-time=chrono::DateTime<chrono::Utc>",
+    ];
+
+    let options = ApiOptions {
+        type_map: mappings
+            .iter()
+            .map(|x| cargo_actix_openapi::parse_type_mapping(x))
+            .collect::<Result<_>>()?,
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "type_map")
+}
+
+#[rstest]
+#[case("request_body")]
+fn test_templates(#[case] case_name: &str) -> Result<()> {
+    let options = ApiOptions {
+        templates: Some("tests/templates".into()),
+        ..Default::default()
+    };
+
+    check_expected(case_name, &options, "templates")
+}
+
+#[rstest]
+#[case("text_plain_object", "Content text/plain must have string schema")]
+#[case("lookahead_pattern", "Invalid pattern \"^(?=.*[0-9]).{8,}$\"")]
+#[case("lookahead_parameter", "Invalid pattern \"^(?!admin)\"")]
+#[case(
+    "digest_security",
+    "Http authentication scheme digest is not supported"
+)]
+fn test_invalid(#[case] case_name: &str, #[case] message: &str) -> Result<()> {
+    let specs = read_spec(&format!("invalid/{case_name}"))?;
+
+    let error =
+        cargo_actix_openapi::generate_api("static/docs.html", &specs, &ApiOptions::default())
+            .expect_err("Spec should be rejected");
+
+    assert!(format!("{error:#}").contains(message), "{error:#}");
+
+    Ok(())
+}